@@ -0,0 +1,492 @@
+//! Parsing of [W3C Datetime](https://www.w3.org/TR/NOTE-datetime), used by `<lastmod>`.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DatetimeParseError {
+    /// The text doesn't match any of the formats of W3C Datetime.
+    InvalidFormat,
+    /// A component (e.g. the month) is out of range.
+    OutOfRange,
+}
+
+/// How precise a [`W3cDatetime`] is, corresponding to the formats allowed by the spec.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash)]
+pub enum Precision {
+    /// `YYYY`
+    Year,
+    /// `YYYY-MM`
+    Month,
+    /// `YYYY-MM-DD`
+    Day,
+    /// `YYYY-MM-DDThh:mmTZD`
+    Minute,
+    /// `YYYY-MM-DDThh:mm:ssTZD`
+    Second,
+    /// `YYYY-MM-DDThh:mm:ss.sTZD`
+    Fraction,
+}
+
+/// A date and time in the [W3C Datetime](https://www.w3.org/TR/NOTE-datetime) format.
+///
+/// The [`Precision`] is preserved.
+/// Components which aren't present are at their first value (e.g. `2004`
+/// is the first of January 2004, 00:00 UTC).
+/// Dates without a time are in UTC.
+///
+/// Datetimes are ordered by the instant they represent, and then by the precision.
+#[derive(Debug, Clone, Copy)]
+pub struct W3cDatetime {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    /// The number of digits in the fraction of a second, for [`Display`]. At most 9.
+    fraction_digits: u8,
+    /// In minutes. [`None`] if no time is present.
+    offset: Option<i16>,
+    precision: Precision,
+}
+impl W3cDatetime {
+    /// Parses `text`, accepting all the formats of the spec.
+    ///
+    /// The time zone designator (`Z`, `+hh:mm` or `-hh:mm`) is required when a time is present.
+    pub fn parse(text: &str) -> Result<Self, DatetimeParseError> {
+        text.parse()
+    }
+    /// Parses `text` in the format of [RFC 822](https://www.rfc-editor.org/rfc/rfc822#section-5),
+    /// as updated by RFC 1123 and RFC 2822, e.g. `Sat, 07 Sep 2002 09:42:31 GMT`.
+    /// This is the format of `<pubDate>` in RSS.
+    ///
+    /// The day of the week is optional and isn't checked. Two-digit years are
+    /// in `1950..=2049`. The time zone is `UT`, `GMT`, `Z`, a North American zone (e.g. `EST`),
+    /// or an offset (e.g. `+0200`); military zones aren't supported.
+    pub fn parse_rfc822(text: &str) -> Result<Self, DatetimeParseError> {
+        const MONTHS: [&str; 12] = [
+            "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+        ];
+        let text = text.split_once(',').map_or(text, |(_, date)| date);
+        let mut parts = text.split_ascii_whitespace();
+        let mut next = || parts.next().ok_or(DatetimeParseError::InvalidFormat);
+        let day = next()?;
+        if day.len() > 2 {
+            return Err(DatetimeParseError::InvalidFormat);
+        }
+        let day = digits(day.as_bytes(), day.len())? as u8;
+        let month = next()?;
+        let month = MONTHS
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(month))
+            .ok_or(DatetimeParseError::InvalidFormat)? as u8
+            + 1;
+        let year = next()?;
+        if year.len() != 2 && year.len() != 4 {
+            return Err(DatetimeParseError::InvalidFormat);
+        }
+        let year = match (year.len(), digits(year.as_bytes(), year.len())?) {
+            (2, year) if year < 50 => year + 2000,
+            (2, year) => year + 1900,
+            (4, year) => year,
+            _ => return Err(DatetimeParseError::InvalidFormat),
+        } as u16;
+        let time = next()?.as_bytes();
+        if time.len() != 5 && time.len() != 8 {
+            return Err(DatetimeParseError::InvalidFormat);
+        }
+        let hour = range(digits(time, 2)? as u8, 0..=23)?;
+        expect(time, 2, b':')?;
+        let minute = range(digits(&time[3..], 2)? as u8, 0..=59)?;
+        let (second, precision) = if time.len() == 8 {
+            expect(time, 5, b':')?;
+            (
+                range(digits(&time[6..], 2)? as u8, 0..=59)?,
+                Precision::Second,
+            )
+        } else {
+            (0, Precision::Minute)
+        };
+        let zone = next()?;
+        let offset = match zone.to_ascii_uppercase().as_str() {
+            "UT" | "GMT" | "Z" => 0,
+            "EDT" => -4 * 60,
+            "EST" | "CDT" => -5 * 60,
+            "CST" | "MDT" => -6 * 60,
+            "MST" | "PDT" => -7 * 60,
+            "PST" => -8 * 60,
+            _ => {
+                let bytes = zone.as_bytes();
+                let sign = match bytes.first() {
+                    Some(b'+') if bytes.len() == 5 => 1,
+                    Some(b'-') if bytes.len() == 5 => -1,
+                    _ => return Err(DatetimeParseError::InvalidFormat),
+                };
+                let hours = range(digits(&bytes[1..], 2)?, 0..=23)?;
+                let minutes = range(digits(&bytes[3..], 2)?, 0..=59)?;
+                sign * (hours * 60 + minutes) as i16
+            }
+        };
+        if parts.next().is_some() {
+            return Err(DatetimeParseError::InvalidFormat);
+        }
+        Ok(Self {
+            year,
+            month,
+            day: range(day, 1..=days_in_month(year, month))?,
+            hour,
+            minute,
+            second,
+            nanosecond: 0,
+            fraction_digits: 0,
+            offset: Some(offset),
+            precision,
+        })
+    }
+    pub fn precision(&self) -> Precision {
+        self.precision
+    }
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+    /// `1..=12`
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+    /// `1..=31`
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+    /// The offset from UTC, in minutes.
+    ///
+    /// Is [`None`] if no time is present, in which case the datetime is in UTC.
+    pub fn offset_minutes(&self) -> Option<i16> {
+        self.offset
+    }
+    /// The number of whole seconds since the Unix epoch (1970-01-01T00:00Z).
+    ///
+    /// Use [`W3cDatetime::nanosecond`] to get the fraction of the second.
+    pub fn unix_timestamp(&self) -> i64 {
+        let days = days_from_civil(
+            i64::from(self.year),
+            i64::from(self.month),
+            i64::from(self.day),
+        );
+        days * 86400
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+            - i64::from(self.offset.unwrap_or(0)) * 60
+    }
+}
+/// The number of days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+///
+/// From <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+/// Parses exactly `len` ASCII digits from the start of `bytes`.
+fn digits(bytes: &[u8], len: usize) -> Result<u32, DatetimeParseError> {
+    let digits = bytes.get(..len).ok_or(DatetimeParseError::InvalidFormat)?;
+    digits.iter().try_fold(0, |acc, &byte| {
+        if byte.is_ascii_digit() {
+            Ok(acc * 10 + u32::from(byte - b'0'))
+        } else {
+            Err(DatetimeParseError::InvalidFormat)
+        }
+    })
+}
+fn expect(bytes: &[u8], pos: usize, byte: u8) -> Result<(), DatetimeParseError> {
+    if bytes.get(pos) == Some(&byte) {
+        Ok(())
+    } else {
+        Err(DatetimeParseError::InvalidFormat)
+    }
+}
+fn range<T: PartialOrd>(
+    value: T,
+    range: std::ops::RangeInclusive<T>,
+) -> Result<T, DatetimeParseError> {
+    if range.contains(&value) {
+        Ok(value)
+    } else {
+        Err(DatetimeParseError::OutOfRange)
+    }
+}
+impl FromStr for W3cDatetime {
+    type Err = DatetimeParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.as_bytes();
+        let mut me = Self {
+            year: digits(bytes, 4)? as u16,
+            month: 1,
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            nanosecond: 0,
+            fraction_digits: 0,
+            offset: None,
+            precision: Precision::Year,
+        };
+        if bytes.len() == 4 {
+            return Ok(me);
+        }
+        expect(bytes, 4, b'-')?;
+        me.month = range(digits(&bytes[5..], 2)? as u8, 1..=12)?;
+        me.precision = Precision::Month;
+        if bytes.len() == 7 {
+            return Ok(me);
+        }
+        expect(bytes, 7, b'-')?;
+        me.day = range(
+            digits(&bytes[8..], 2)? as u8,
+            1..=days_in_month(me.year, me.month),
+        )?;
+        me.precision = Precision::Day;
+        if bytes.len() == 10 {
+            return Ok(me);
+        }
+        expect(bytes, 10, b'T')?;
+        me.hour = range(digits(&bytes[11..], 2)? as u8, 0..=23)?;
+        expect(bytes, 13, b':')?;
+        me.minute = range(digits(&bytes[14..], 2)? as u8, 0..=59)?;
+        me.precision = Precision::Minute;
+        let mut pos = 16;
+        if bytes.get(pos) == Some(&b':') {
+            me.second = range(digits(&bytes[pos + 1..], 2)? as u8, 0..=59)?;
+            me.precision = Precision::Second;
+            pos += 3;
+            if bytes.get(pos) == Some(&b'.') {
+                pos += 1;
+                let fraction_len = bytes[pos..]
+                    .iter()
+                    .take_while(|byte| byte.is_ascii_digit())
+                    .count();
+                if fraction_len == 0 {
+                    return Err(DatetimeParseError::InvalidFormat);
+                }
+                // Only nanosecond precision is kept.
+                let kept = fraction_len.min(9);
+                me.nanosecond = digits(&bytes[pos..], kept)? * 10_u32.pow(9 - kept as u32);
+                me.fraction_digits = kept as u8;
+                me.precision = Precision::Fraction;
+                pos += fraction_len;
+            }
+        }
+        let tzd = &bytes[pos..];
+        me.offset = Some(match tzd.first() {
+            Some(b'Z') if tzd.len() == 1 => 0,
+            Some(&sign) if (sign == b'+' || sign == b'-') && tzd.len() == 6 => {
+                let hours = range(digits(&tzd[1..], 2)?, 0..=23)?;
+                expect(tzd, 3, b':')?;
+                let minutes = range(digits(&tzd[4..], 2)?, 0..=59)?;
+                let offset = (hours * 60 + minutes) as i16;
+                if sign == b'-' {
+                    -offset
+                } else {
+                    offset
+                }
+            }
+            _ => return Err(DatetimeParseError::InvalidFormat),
+        });
+        Ok(me)
+    }
+}
+impl Display for W3cDatetime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if self.precision >= Precision::Month {
+            write!(f, "-{:02}", self.month)?;
+        }
+        if self.precision >= Precision::Day {
+            write!(f, "-{:02}", self.day)?;
+        }
+        if self.precision >= Precision::Minute {
+            write!(f, "T{:02}:{:02}", self.hour, self.minute)?;
+        }
+        if self.precision >= Precision::Second {
+            write!(f, ":{:02}", self.second)?;
+        }
+        if self.precision >= Precision::Fraction {
+            let digits = format!("{:09}", self.nanosecond);
+            f.write_str(".")?;
+            f.write_str(&digits[..usize::from(self.fraction_digits)])?;
+        }
+        match self.offset {
+            None => {}
+            Some(0) => f.write_str("Z")?,
+            Some(offset) => {
+                let sign = if offset < 0 { '-' } else { '+' };
+                let offset = offset.unsigned_abs();
+                write!(f, "{sign}{:02}:{:02}", offset / 60, offset % 60)?;
+            }
+        }
+        Ok(())
+    }
+}
+impl Ord for W3cDatetime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.unix_timestamp()
+            .cmp(&other.unix_timestamp())
+            .then(self.nanosecond.cmp(&other.nanosecond))
+            .then(self.precision.cmp(&other.precision))
+    }
+}
+impl PartialOrd for W3cDatetime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl PartialEq for W3cDatetime {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for W3cDatetime {}
+impl Hash for W3cDatetime {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.unix_timestamp().hash(state);
+        self.nanosecond.hash(state);
+        self.precision.hash(state);
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<W3cDatetime> for chrono::DateTime<chrono::FixedOffset> {
+    fn from(datetime: W3cDatetime) -> Self {
+        use chrono::TimeZone;
+        let offset = chrono::FixedOffset::east_opt(i32::from(datetime.offset.unwrap_or(0)) * 60)
+            .expect("offset is validated when parsing");
+        offset
+            .timestamp_opt(datetime.unix_timestamp(), datetime.nanosecond)
+            .single()
+            .expect("all components are validated when parsing")
+    }
+}
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> TryFrom<chrono::DateTime<Tz>> for W3cDatetime {
+    type Error = DatetimeParseError;
+    /// Converts with [`Precision::Fraction`] if there's a fraction of a second,
+    /// otherwise [`Precision::Second`].
+    ///
+    /// Returns [`DatetimeParseError::OutOfRange`] if the year isn't in `0..=9999`.
+    fn try_from(datetime: chrono::DateTime<Tz>) -> Result<Self, Self::Error> {
+        use chrono::{Datelike, Offset, Timelike};
+        let offset = datetime.offset().fix().local_minus_utc() / 60;
+        let local = datetime.naive_local();
+        Ok(Self::from_components(
+            range(local.year(), 0..=9999)? as u16,
+            local.month() as u8,
+            local.day() as u8,
+            local.hour() as u8,
+            local.minute() as u8,
+            local.second() as u8,
+            // Leap seconds are represented as nanoseconds >= 1_000_000_000.
+            local.nanosecond().min(999_999_999),
+            offset as i16,
+        ))
+    }
+}
+#[cfg(feature = "time")]
+impl From<W3cDatetime> for time::OffsetDateTime {
+    fn from(datetime: W3cDatetime) -> Self {
+        let offset =
+            time::UtcOffset::from_whole_seconds(i32::from(datetime.offset.unwrap_or(0)) * 60)
+                .expect("offset is validated when parsing");
+        time::OffsetDateTime::from_unix_timestamp(datetime.unix_timestamp())
+            .expect("all components are validated when parsing")
+            .replace_nanosecond(datetime.nanosecond)
+            .expect("nanosecond is less than 10^9")
+            .to_offset(offset)
+    }
+}
+#[cfg(feature = "time")]
+impl TryFrom<time::OffsetDateTime> for W3cDatetime {
+    type Error = DatetimeParseError;
+    /// Converts with [`Precision::Fraction`] if there's a fraction of a second,
+    /// otherwise [`Precision::Second`].
+    ///
+    /// Returns [`DatetimeParseError::OutOfRange`] if the year isn't in `0..=9999`.
+    fn try_from(datetime: time::OffsetDateTime) -> Result<Self, Self::Error> {
+        Ok(Self::from_components(
+            range(datetime.year(), 0..=9999)? as u16,
+            datetime.month() as u8,
+            datetime.day(),
+            datetime.hour(),
+            datetime.minute(),
+            datetime.second(),
+            datetime.nanosecond(),
+            (datetime.offset().whole_seconds() / 60) as i16,
+        ))
+    }
+}
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl W3cDatetime {
+    #[allow(clippy::too_many_arguments)]
+    fn from_components(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+        nanosecond: u32,
+        offset: i16,
+    ) -> Self {
+        let (precision, fraction_digits) = if nanosecond == 0 {
+            (Precision::Second, 0)
+        } else {
+            // Don't print trailing zeroes.
+            let mut digits = 9;
+            let mut n = nanosecond;
+            while n % 10 == 0 {
+                n /= 10;
+                digits -= 1;
+            }
+            (Precision::Fraction, digits)
+        };
+        Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            // Leap seconds aren't allowed by the spec.
+            second: second.min(59),
+            nanosecond,
+            fraction_digits,
+            offset: Some(offset),
+            precision,
+        }
+    }
+}
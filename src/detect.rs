@@ -0,0 +1,240 @@
+//! Detection of the format of input fetched from a URL which should be a sitemap.
+//!
+//! Use [`Sitemap::parse`] to get the appropriate parser, or [`detect`] to only sniff the format.
+
+use crate::feed::{self, Feed};
+#[cfg(feature = "gzip")]
+use crate::gzip;
+use crate::{from_utf8, Document, DocumentKind, Error, ParseOptions, TextSitemap};
+use quick_xml::events::Event;
+use quick_xml::name::ResolveResult;
+use quick_xml::NsReader;
+
+/// The first bytes of gzip data, also available without the `gzip` feature.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Elements which start an HTML page, with or without the `<html>` root.
+const HTML_ELEMENTS: [&[u8]; 13] = [
+    b"html", b"head", b"body", b"title", b"meta", b"link", b"base", b"script", b"style", b"div",
+    b"p", b"h1", b"pre",
+];
+
+/// The format of some input, as found by [`detect`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    /// A `<urlset>`, see [`DocumentKind::Urlset`].
+    Urlset,
+    /// A `<sitemapindex>`, see [`DocumentKind::SitemapIndex`].
+    SitemapIndex,
+    /// A text sitemap, see [`TextSitemap`].
+    Text,
+    /// A RSS feed, see [`feed::FeedKind::Rss`].
+    Rss,
+    /// An Atom feed, see [`feed::FeedKind::Atom`].
+    Atom,
+    /// Gzipped data, which has to be decompressed first.
+    Gzip,
+    /// An HTML page, e.g. an error page or a "soft 404" served instead of the sitemap.
+    Html,
+    /// Empty input, or only whitespace.
+    Empty,
+    /// XML with another root element, malformed XML, UTF-16,
+    /// or text which doesn't start with a URL (e.g. JSON or "Not Found").
+    Unknown,
+}
+
+/// Sniffs the format of `bytes`, from the magic bytes, the root element, and its namespace.
+///
+/// Input which doesn't start with `<` (after a byte order mark and whitespace) is a
+/// [`Format::Text`] if its first line is an absolute `http` or `https` URL.
+/// Root elements of HTML pages without `<html>`, e.g. `<head>` or `<body>`, are a [`Format::Html`].
+/// The namespace of `<urlset>` and `<sitemapindex>` isn't checked; see [`crate::NamespaceMode`].
+pub fn detect(bytes: &[u8]) -> Format {
+    if bytes.starts_with(&GZIP_MAGIC) {
+        return Format::Gzip;
+    }
+    if bytes.starts_with(b"\xff\xfe") || bytes.starts_with(b"\xfe\xff") {
+        // UTF-16, which has to be decoded first.
+        return Format::Unknown;
+    }
+    let bytes = trim_start(bytes);
+    if bytes.is_empty() {
+        return Format::Empty;
+    }
+    if !bytes.starts_with(b"<") {
+        return if starts_with_url(bytes) {
+            Format::Text
+        } else {
+            Format::Unknown
+        };
+    }
+    if starts_with_ignore_case(bytes, b"<!doctype html") || starts_with_ignore_case(bytes, b"<html")
+    {
+        return Format::Html;
+    }
+    let mut reader = NsReader::from_reader(bytes);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(start)) | Ok(Event::Empty(start)) => {
+                let (namespace, name) = reader.resolve_element(start.name());
+                let namespace = match namespace {
+                    ResolveResult::Bound(namespace) => Some(namespace.into_inner()),
+                    _ => None,
+                };
+                return match (namespace, name.into_inner()) {
+                    (_, b"urlset") => Format::Urlset,
+                    (_, b"sitemapindex") => Format::SitemapIndex,
+                    (None, b"rss") => Format::Rss,
+                    (Some(namespace), b"feed")
+                        if namespace == feed::ATOM_NAMESPACE.as_bytes()
+                            || namespace == feed::ATOM_0_3_NAMESPACE.as_bytes() =>
+                    {
+                        Format::Atom
+                    }
+                    (_, name)
+                        if HTML_ELEMENTS
+                            .iter()
+                            .any(|element| name.eq_ignore_ascii_case(element)) =>
+                    {
+                        Format::Html
+                    }
+                    _ => Format::Unknown,
+                };
+            }
+            Ok(Event::DocType(doctype)) if starts_with_ignore_case(&doctype, b"html") => {
+                return Format::Html
+            }
+            Ok(Event::Eof) | Err(_) => return Format::Unknown,
+            Ok(_) => {}
+        }
+        buf.clear();
+    }
+}
+/// Strip the byte order mark of UTF-8 and the leading whitespace of `bytes`.
+fn trim_start(bytes: &[u8]) -> &[u8] {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    let start = bytes
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    &bytes[start..]
+}
+/// Check if the first line of `bytes` is an absolute `http` or `https` URL.
+fn starts_with_url(bytes: &[u8]) -> bool {
+    let line = bytes
+        .split(|byte| *byte == b'\n')
+        .next()
+        .unwrap_or_default();
+    let end = line
+        .iter()
+        .rposition(|byte| !byte.is_ascii_whitespace())
+        .map_or(0, |index| index + 1);
+    let line = &line[..end];
+    let rest = [&b"http://"[..], b"https://"]
+        .iter()
+        .find(|scheme| starts_with_ignore_case(line, scheme))
+        .map(|scheme| &line[scheme.len()..]);
+    matches!(rest, Some(rest) if !rest.is_empty()
+        && !rest.starts_with(b"/")
+        && !rest.iter().any(u8::is_ascii_whitespace))
+}
+fn starts_with_ignore_case(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes
+        .get(..prefix.len())
+        .map_or(false, |start| start.eq_ignore_ascii_case(prefix))
+}
+
+/// A parsed sitemap of any of the supported formats.
+pub enum Sitemap<'a> {
+    /// Use [`Document::iterate`].
+    Urlset(Document<'a>),
+    /// Use [`Document::iterate_sitemaps`].
+    SitemapIndex(Document<'a>),
+    /// Use [`TextSitemap::iterate`].
+    Text(TextSitemap<'a>),
+    /// Use [`Feed::iterate`].
+    Feed(Feed<'a>),
+}
+impl<'a> Sitemap<'a> {
+    /// [`detect`]s the format of `bytes`, which must be UTF-8, and parses them.
+    ///
+    /// See [`Document::from_bytes`] for other encodings.
+    ///
+    /// Returns [`Error::Gzipped`] for gzipped input, [`Error::Html`] for HTML pages,
+    /// [`Error::Empty`] for empty input, and [`Error::UnknownFormat`] for other XML or text.
+    /// With the `gzip` feature, use `Sitemap::parse_with_buffer` to decompress gzipped input.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        Self::parse_with_options(bytes, ParseOptions::default())
+    }
+    /// Like [`Sitemap::parse`], with `options`.
+    pub fn parse_with_options(bytes: &'a [u8], options: ParseOptions) -> Result<Self, Error> {
+        let format = detect(bytes);
+        match format {
+            Format::Gzip => return Err(Error::Gzipped),
+            Format::Html => return Err(Error::Html),
+            _ => {}
+        }
+        let text = from_utf8(bytes)?;
+        match format {
+            Format::Empty => return Err(Error::Empty),
+            Format::Unknown if !trim_start(bytes).starts_with(b"<") => {
+                return Err(Error::UnknownFormat)
+            }
+            _ => {}
+        }
+        match format {
+            Format::Text => TextSitemap::parse_with_options(text, options).map(Self::Text),
+            Format::Rss | Format::Atom => Feed::parse_with_options(text, options).map(Self::Feed),
+            _ => {
+                // Also gives the parse error of malformed XML.
+                let doc = Document::parse_with_options(text, options)?;
+                match doc.kind() {
+                    Some(DocumentKind::Urlset) => Ok(Self::Urlset(doc)),
+                    Some(DocumentKind::SitemapIndex) => Ok(Self::SitemapIndex(doc)),
+                    None if format == Format::Unknown => Err(Error::UnknownFormat),
+                    None => Err(Error::UnexpectedNamespace(
+                        doc.namespace().map(str::to_owned),
+                    )),
+                }
+            }
+        }
+    }
+    /// Like [`Sitemap::parse_with_options`], but gzipped input is decompressed into `buffer`,
+    /// up to the [`ParseOptions::max_size`] (or [`gzip::DEFAULT_MAX_SIZE`]), and detected again.
+    ///
+    /// Returns [`Error::TooLarge`] if the decompressed input exceeds the maximum size,
+    /// and [`Error::InvalidGzip`] if it's corrupt.
+    #[cfg(feature = "gzip")]
+    pub fn parse_with_buffer(
+        bytes: &'a [u8],
+        buffer: &'a mut Vec<u8>,
+        options: ParseOptions,
+    ) -> Result<Self, Error> {
+        if !gzip::is_gzip(bytes) {
+            return Self::parse_with_options(bytes, options);
+        }
+        let max_size = options.max_size.unwrap_or(gzip::DEFAULT_MAX_SIZE);
+        *buffer = gzip::decompress(bytes, max_size)
+            .map_err(
+                |err| match err.get_ref().and_then(|inner| inner.downcast_ref()) {
+                    Some(gzip::TooLarge { max_size }) => Error::TooLarge(*max_size),
+                    _ => Error::InvalidGzip(err.to_string()),
+                },
+            )?
+            .into_owned();
+        Self::parse_with_options(buffer, options)
+    }
+    /// Returns the format of this sitemap.
+    pub fn format(&self) -> Format {
+        match self {
+            Self::Urlset(_) => Format::Urlset,
+            Self::SitemapIndex(_) => Format::SitemapIndex,
+            Self::Text(_) => Format::Text,
+            Self::Feed(feed) => match feed.kind() {
+                feed::FeedKind::Rss => Format::Rss,
+                feed::FeedKind::Atom => Format::Atom,
+            },
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use crate::ParseOptions;
+use log::{error, warn};
+use std::fmt::{self, Display};
+
+pub use roxmltree::TextPos;
+
+/// How severe a [`Diagnostic`] is.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum Severity {
+    /// The entry is kept, but a field was ignored or overridden.
+    Warning,
+    /// The entry was dropped.
+    Error,
+    /// The whole document was rejected, as set by the [`ParseOptions`].
+    Fatal,
+}
+/// The kind of problem found in an entry.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DiagnosticKind {
+    /// The entry has no `<loc>`.
+    MissingLocation,
+    /// A line of a [`crate::TextSitemap`] is blank.
+    BlankLine,
+    /// The entry has multiple `<loc>`. By default, the entry is dropped.
+    DuplicateLocation,
+    /// The entry has multiple `<lastmod>`. By default, the last one is used.
+    DuplicateLastModified,
+    /// The entry has multiple `<changefreq>`. By default, the last valid one is used.
+    DuplicateChangeFrequency,
+    /// The entry has multiple `<priority>`. By default, the last valid one is used.
+    DuplicatePriority,
+    /// The `<lastmod>` isn't in the [W3C Datetime](https://www.w3.org/TR/NOTE-datetime) format.
+    ///
+    /// By default, the text is still available in the entry.
+    InvalidLastModified(String),
+    /// The `<changefreq>` isn't one of the values in [`crate::Frequency`].
+    InvalidChangeFrequency(String),
+    /// The `<priority>` isn't a floating-point number.
+    InvalidPriority(String),
+    /// The `<priority>` isn't in the range `0.0..=1.0`.
+    ///
+    /// Contains the text, as written.
+    PriorityOutOfRange(String),
+    /// A field of an extension (e.g. `<image:title>`) occurs multiple times.
+    /// The last one is used.
+    ///
+    /// Contains the name of the field, with the conventional prefix.
+    DuplicateExtensionField(String),
+    /// A required field of an extension is missing. The extension element is ignored.
+    ///
+    /// Contains the names of the extension element (e.g. `image:image`)
+    /// and the field (e.g. `image:loc`), with the conventional prefix.
+    /// Fields which are attributes (e.g. `hreflang` of `xhtml:link`) have no prefix.
+    MissingExtensionField { element: String, field: String },
+    /// An extension requires at least one of some fields, but none are present.
+    /// The extension element is ignored.
+    ///
+    /// E.g. `<video:video>` needs a `<video:content_loc>` or a `<video:player_loc>`.
+    MissingOneOfExtensionFields {
+        element: String,
+        fields: Vec<String>,
+    },
+    /// A field of an extension has invalid format.
+    /// Unless stated otherwise in the docs of the field, it's ignored.
+    InvalidExtensionField { field: String, value: String },
+    /// A field of an extension is out of range. By default, it's ignored.
+    ExtensionFieldOutOfRange { field: String, value: String },
+    /// More than [`crate::news::MAX_ENTRIES`] entries with a `<news:news>` in the document.
+    ///
+    /// Added to every entry past the limit. The news are still parsed.
+    TooManyNewsEntries,
+    /// More than [`crate::writer::MAX_ENTRIES`] entries in the document.
+    ///
+    /// Added to every entry past the limit. The entries are still parsed.
+    TooManyEntries,
+    /// The document is larger than [`crate::writer::MAX_SIZE`].
+    ///
+    /// Added to every entry which ends past the limit. The entries are still parsed.
+    FileTooLarge,
+    /// The `<loc>` is longer than [`crate::schema::MAX_LOCATION_LENGTH`].
+    ///
+    /// Contains the number of characters. The location is still used.
+    LocationTooLong(usize),
+    /// The `<loc>` isn't an absolute `http` or `https` URL.
+    /// Only checked with the `url` feature; see `location::parse`.
+    ///
+    /// By default, the location is still used.
+    InvalidLocation(String),
+    /// The `<loc>` has characters which should be percent-encoded, e.g. spaces.
+    /// Only checked with the `url` feature; see `location::is_percent_encoded`.
+    ///
+    /// The location is still used.
+    LocationNotPercentEncoded(String),
+    /// An entry or field isn't in a namespace accepted by the [`crate::NamespaceMode`].
+    ///
+    /// Fields in the wrong namespace are ignored, and available in
+    /// [`crate::UrlEntry::extensions`].
+    UnexpectedNamespace {
+        /// The local name of the element.
+        element: String,
+        /// The namespace found.
+        namespace: Option<String>,
+    },
+}
+impl DiagnosticKind {
+    /// Get the [`Severity`] of this kind of diagnostic with the default [`ParseOptions`].
+    pub fn severity(&self) -> Severity {
+        ParseOptions::default().severity(self)
+    }
+}
+impl Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingLocation => f.write_str("Expected <loc>, but found none."),
+            Self::BlankLine => f.write_str("Blank line in text sitemap."),
+            Self::DuplicateLocation => f.write_str("Multiple <loc> in entry."),
+            Self::DuplicateLastModified => f.write_str("Multiple <lastmod> in entry."),
+            Self::DuplicateChangeFrequency => f.write_str("Multiple <changefreq> in entry."),
+            Self::DuplicatePriority => f.write_str("Multiple <priority> in entry."),
+            Self::InvalidLastModified(text) => write!(
+                f,
+                "<lastmod> has invalid format: {text:?}. Expected W3C Datetime."
+            ),
+            Self::InvalidChangeFrequency(text) => {
+                write!(f, "<changefreq> has invalid format: {text:?}")
+            }
+            Self::InvalidPriority(text) => write!(
+                f,
+                "<priority> has invalid format: {text:?}. Expected floating-point number."
+            ),
+            Self::PriorityOutOfRange(text) => write!(f, "<priority> {text} is out of range"),
+            Self::DuplicateExtensionField(name) => write!(f, "Multiple <{name}> in entry."),
+            Self::MissingExtensionField { element, field } => {
+                write!(f, "Expected <{field}> in <{element}>, but found none.")
+            }
+            Self::MissingOneOfExtensionFields { element, fields } => {
+                let fields: Vec<_> = fields.iter().map(|field| format!("<{field}>")).collect();
+                write!(
+                    f,
+                    "Expected one of {} in <{element}>, but found none.",
+                    fields.join(" or ")
+                )
+            }
+            Self::InvalidExtensionField { field, value } => {
+                write!(f, "<{field}> has invalid format: {value:?}")
+            }
+            Self::ExtensionFieldOutOfRange { field, value } => {
+                write!(f, "<{field}> {value} is out of range")
+            }
+            Self::TooManyNewsEntries => write!(
+                f,
+                "More than {} entries with <news:news> in document.",
+                crate::news::MAX_ENTRIES
+            ),
+            Self::TooManyEntries => write!(
+                f,
+                "More than {} entries in document.",
+                crate::writer::MAX_ENTRIES
+            ),
+            Self::FileTooLarge => write!(
+                f,
+                "Document is larger than {} bytes.",
+                crate::writer::MAX_SIZE
+            ),
+            Self::LocationTooLong(length) => write!(
+                f,
+                "<loc> has {length} characters, more than {}.",
+                crate::schema::MAX_LOCATION_LENGTH
+            ),
+            Self::InvalidLocation(text) => write!(
+                f,
+                "<loc> is invalid: {text:?}. Expected absolute http or https URL."
+            ),
+            Self::LocationNotPercentEncoded(text) => {
+                write!(f, "<loc> isn't percent-encoded: {text:?}")
+            }
+            Self::UnexpectedNamespace {
+                element,
+                namespace: Some(namespace),
+            } => write!(f, "<{element}> is in unexpected namespace {namespace:?}."),
+            Self::UnexpectedNamespace {
+                element,
+                namespace: None,
+            } => write!(f, "<{element}> has no namespace."),
+        }
+    }
+}
+/// A problem found in an entry of a sitemap.
+///
+/// These are recoverable; the rest of the document is still parsed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    /// The index of the entry (`<url>` or `<sitemap>`) in the document, starting at `0`.
+    pub entry: usize,
+    /// The position in the text where the problem was found.
+    pub position: TextPos,
+    /// The severity, given the [`ParseOptions`] used.
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+}
+impl Diagnostic {
+    /// Log this diagnostic using [`log`], as [`crate::Document::iterate`] does.
+    pub fn log(&self) {
+        match self.severity {
+            Severity::Warning => warn!("{self}"),
+            Severity::Error | Severity::Fatal => error!("{self}"),
+        }
+    }
+}
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "entry {} ({}): {}", self.entry, self.position, self.kind)
+    }
+}
+/// An entry of a sitemap, along with the problems found in it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Report<T> {
+    /// The index of the entry in the document, starting at `0`.
+    pub index: usize,
+    /// The entry, if it was valid.
+    ///
+    /// Is [`None`] if any of the [`Report::diagnostics`] have [`Severity::Error`] or worse.
+    pub entry: Option<T>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+impl<T> Report<T> {
+    /// Log all [`Report::diagnostics`] and return [`Report::entry`].
+    pub fn log(self) -> Option<T> {
+        for diagnostic in &self.diagnostics {
+            diagnostic.log();
+        }
+        self.entry
+    }
+}
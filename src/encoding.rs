@@ -0,0 +1,163 @@
+//! Transcoding of sitemaps which aren't UTF-8, with the `encoding_rs` feature.
+//!
+//! The encoding is taken from the byte order mark, then the `encoding` of the XML declaration
+//! (`<?xml version="1.0" encoding="ISO-8859-1"?>`), and defaults to UTF-8.
+//! UTF-16 without a byte order mark is recognized from the start of the XML declaration.
+//!
+//! Valid UTF-8 input is borrowed as-is, so [`decode`] can be used on all input.
+//! Parse the [`Decoded::text`] with [`crate::Document::parse`].
+
+use crate::declared_encoding;
+pub use encoding_rs::Encoding;
+use encoding_rs::{UTF_16BE, UTF_16LE, UTF_8};
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+
+/// A disagreement between the declared and the actual encoding of the input.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Mismatch {
+    /// The byte order mark (or the start of the document) shows another encoding
+    /// than the one declared, or the declared encoding can't apply to the input
+    /// (e.g. UTF-16 without a byte order mark). The actual encoding is used.
+    Declaration {
+        declared: String,
+        actual: &'static Encoding,
+    },
+    /// The declared encoding isn't known. UTF-8 is used.
+    UnknownEncoding(String),
+    /// The input isn't valid in the used encoding. Invalid sequences are replaced by `U+FFFD`.
+    Malformed(&'static Encoding),
+    /// Another encoding is declared (and used), but the input is valid UTF-8
+    /// with non-ASCII characters, so it's likely UTF-8.
+    LooksLikeUtf8(&'static Encoding),
+}
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Declaration { declared, actual } => write!(
+                f,
+                "declared encoding {:?}, but the document is {}",
+                declared,
+                actual.name()
+            ),
+            Self::UnknownEncoding(declared) => {
+                write!(f, "unknown declared encoding {:?}, using UTF-8", declared)
+            }
+            Self::Malformed(encoding) => write!(f, "the document isn't valid {}", encoding.name()),
+            Self::LooksLikeUtf8(encoding) => write!(
+                f,
+                "declared encoding {}, but the document looks like UTF-8",
+                encoding.name()
+            ),
+        }
+    }
+}
+
+/// The input of [`decode`], as UTF-8.
+#[derive(Debug, Clone)]
+pub struct Decoded<'a> {
+    text: Cow<'a, str>,
+    encoding: &'static Encoding,
+    declared: Option<String>,
+    mismatches: Vec<Mismatch>,
+}
+impl<'a> Decoded<'a> {
+    /// The decoded text, without a byte order mark.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    /// Returns the decoded text, which is borrowed from the input if it was valid UTF-8.
+    pub fn into_text(self) -> Cow<'a, str> {
+        self.text
+    }
+    /// The encoding the input was decoded from.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+    /// The `encoding` of the XML declaration, as written.
+    pub fn declared(&self) -> Option<&str> {
+        self.declared.as_deref()
+    }
+    /// The disagreements between the declared and the actual encoding.
+    pub fn mismatches(&self) -> &[Mismatch] {
+        &self.mismatches
+    }
+}
+
+/// Decodes `bytes` from the encoding given by the byte order mark or XML declaration.
+///
+/// The byte order mark takes precedence over the declaration.
+/// Disagreements are reported in [`Decoded::mismatches`].
+pub fn decode(bytes: &[u8]) -> Decoded<'_> {
+    let mut mismatches = Vec::new();
+    let sniffed = Encoding::for_bom(bytes).or_else(|| {
+        if bytes.starts_with(b"<\0?\0") {
+            Some((UTF_16LE, 0))
+        } else if bytes.starts_with(b"\0<\0?") {
+            Some((UTF_16BE, 0))
+        } else {
+            None
+        }
+    });
+    let ((text, malformed), encoding, declared) = match sniffed {
+        Some((encoding, bom_length)) => {
+            let decoded = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+            let declared = declared_encoding(&decoded.0).map(str::to_owned);
+            if let Some(declared) = &declared {
+                let agrees = Encoding::for_label(declared.as_bytes()) == Some(encoding)
+                    || (declared.eq_ignore_ascii_case("utf-16")
+                        && (encoding == UTF_16LE || encoding == UTF_16BE));
+                if !agrees {
+                    mismatches.push(Mismatch::Declaration {
+                        declared: declared.clone(),
+                        actual: encoding,
+                    });
+                }
+            }
+            (decoded, encoding, declared)
+        }
+        None => {
+            // The declaration is ASCII, so read it before decoding.
+            let end = bytes
+                .iter()
+                .position(|byte| *byte == b'>')
+                .map_or(0, |index| index + 1);
+            let declared = std::str::from_utf8(&bytes[..end])
+                .ok()
+                .and_then(declared_encoding);
+            let encoding =
+                match declared.map(|label| (label, Encoding::for_label(label.as_bytes()))) {
+                    None => UTF_8,
+                    Some((label, None)) => {
+                        mismatches.push(Mismatch::UnknownEncoding(label.to_owned()));
+                        UTF_8
+                    }
+                    Some((label, Some(encoding))) => {
+                        // UTF-16 without a byte order mark would have been sniffed.
+                        let actual = encoding.output_encoding();
+                        if actual != encoding {
+                            mismatches.push(Mismatch::Declaration {
+                                declared: label.to_owned(),
+                                actual,
+                            });
+                        }
+                        actual
+                    }
+                };
+            if encoding != UTF_8 && !bytes.is_ascii() && std::str::from_utf8(bytes).is_ok() {
+                mismatches.push(Mismatch::LooksLikeUtf8(encoding));
+            }
+            let decoded = encoding.decode_without_bom_handling(bytes);
+            (decoded, encoding, declared.map(str::to_owned))
+        }
+    };
+    if malformed {
+        mismatches.push(Mismatch::Malformed(encoding));
+    }
+    Decoded {
+        text,
+        encoding,
+        declared,
+        mismatches,
+    }
+}
@@ -0,0 +1,113 @@
+use crate::parse::Element;
+
+/// An attribute of an [`ExtensionElement`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ExtensionAttribute<'a> {
+    /// The namespace URI of the attribute. Unprefixed attributes have none.
+    pub namespace: Option<&'a str>,
+    /// The local name of the attribute.
+    pub name: &'a str,
+    pub value: &'a str,
+}
+/// A child element of a `<url>` which isn't part of the sitemap protocol
+/// or of the extensions this crate parses (e.g. PageMap or vendor metadata).
+///
+/// Namespace declarations aren't included in [`ExtensionElement::attributes`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExtensionElement<'a> {
+    /// The namespace URI of the element.
+    pub namespace: Option<&'a str>,
+    /// The local name of the element.
+    pub name: &'a str,
+    pub attributes: Vec<ExtensionAttribute<'a>>,
+    /// The text before the first child element, with entities resolved.
+    pub text: Option<&'a str>,
+    /// The source between the start and end tags, as-is.
+    ///
+    /// Prefixes in this are declared on the element or its ancestors,
+    /// so resolve them using [`ExtensionElement::namespace`] and [`ExtensionAttribute::namespace`].
+    pub inner_xml: &'a str,
+}
+impl<'a> ExtensionElement<'a> {
+    /// Get the value of the attribute with the `name` and no namespace.
+    pub fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.namespace.is_none() && attribute.name == name)
+            .map(|attribute| attribute.value)
+    }
+}
+
+/// An owned version of [`ExtensionAttribute`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OwnedExtensionAttribute {
+    /// See [`ExtensionAttribute::namespace`].
+    pub namespace: Option<String>,
+    /// See [`ExtensionAttribute::name`].
+    pub name: String,
+    /// See [`ExtensionAttribute::value`].
+    pub value: String,
+}
+/// An owned version of [`ExtensionElement`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OwnedExtensionElement {
+    /// See [`ExtensionElement::namespace`].
+    pub namespace: Option<String>,
+    /// See [`ExtensionElement::name`].
+    pub name: String,
+    /// See [`ExtensionElement::attributes`].
+    pub attributes: Vec<OwnedExtensionAttribute>,
+    /// See [`ExtensionElement::text`].
+    pub text: Option<String>,
+    /// See [`ExtensionElement::inner_xml`].
+    pub inner_xml: String,
+}
+impl OwnedExtensionElement {
+    /// Borrow this as a [`ExtensionElement`].
+    pub fn as_element(&self) -> ExtensionElement<'_> {
+        ExtensionElement {
+            namespace: self.namespace.as_deref(),
+            name: &self.name,
+            attributes: self
+                .attributes
+                .iter()
+                .map(|attribute| ExtensionAttribute {
+                    namespace: attribute.namespace.as_deref(),
+                    name: &attribute.name,
+                    value: &attribute.value,
+                })
+                .collect(),
+            text: self.text.as_deref(),
+            inner_xml: &self.inner_xml,
+        }
+    }
+}
+impl<'a> From<ExtensionElement<'a>> for OwnedExtensionElement {
+    fn from(element: ExtensionElement<'a>) -> Self {
+        Self {
+            namespace: element.namespace.map(str::to_owned),
+            name: element.name.to_owned(),
+            attributes: element
+                .attributes
+                .into_iter()
+                .map(|attribute| OwnedExtensionAttribute {
+                    namespace: attribute.namespace.map(str::to_owned),
+                    name: attribute.name.to_owned(),
+                    value: attribute.value.to_owned(),
+                })
+                .collect(),
+            text: element.text.map(str::to_owned),
+            inner_xml: element.inner_xml.to_owned(),
+        }
+    }
+}
+
+pub(crate) fn parse<'a, E: Element<'a>>(node: E) -> ExtensionElement<'a> {
+    ExtensionElement {
+        namespace: node.namespace(),
+        name: node.name(),
+        attributes: node.attributes(),
+        text: node.text(),
+        inner_xml: node.inner_xml(),
+    }
+}
@@ -0,0 +1,256 @@
+//! [RSS 2.0](https://www.rssboard.org/rss-specification) and
+//! [Atom](https://www.rfc-editor.org/rfc/rfc4287) feeds, which search engines
+//! [accept as sitemaps](https://sitemaps.org/protocol.html#otherformats).
+//!
+//! Feeds usually list the recent changes of a site (e.g. `/feed/` of WordPress),
+//! so they're useful as incremental sitemaps.
+
+use crate::parse::{check_limits, location_problems, LineIndex};
+use crate::{
+    check_fatal, check_size, from_utf8, Diagnostic, DiagnosticKind, Error, InvalidPolicy,
+    ParseOptions, Report, Severity, TextPos, UrlEntry, W3cDatetime,
+};
+use roxmltree::Node;
+use std::fmt::Debug;
+
+/// The XML namespace of Atom 1.0.
+pub const ATOM_NAMESPACE: &str = "http://www.w3.org/2005/Atom";
+/// The XML namespace of Atom 0.3.
+pub const ATOM_0_3_NAMESPACE: &str = "http://purl.org/atom/ns#";
+
+/// The format of a [`Feed`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FeedKind {
+    /// An `<rss>` with `<item>`s, whose `<link>` is the location
+    /// and `<pubDate>` the last modification.
+    Rss,
+    /// An Atom 1.0 or 0.3 `<feed>` with `<entry>`s, whose `<link href>` is the location
+    /// and `<updated>` (or `<modified>` in Atom 0.3) the last modification.
+    ///
+    /// Only links without a `rel` or with `rel="alternate"` are used.
+    Atom,
+}
+
+/// A RSS or Atom feed, whose items are [`UrlEntry`]s.
+///
+/// The entries only have a [`UrlEntry::location`] and [`UrlEntry::last_modified`].
+/// The `<pubDate>` of RSS is converted from [RFC 822](W3cDatetime::parse_rfc822)
+/// to W3C Datetime.
+///
+/// The same checks as for the `<loc>` and `<lastmod>` of [`crate::Document`] are applied.
+#[derive(Debug)]
+pub struct Feed<'a> {
+    doc: roxmltree::Document<'a>,
+    lines: LineIndex,
+    kind: FeedKind,
+    /// The `<pubDate>` of each RSS item, converted to W3C Datetime if valid.
+    dates: Vec<Option<String>>,
+    options: ParseOptions,
+}
+impl<'a> Feed<'a> {
+    /// Parses `xml_document`.
+    ///
+    /// Returns [`Error::FeedMissing`] if the root is neither a `<rss>` nor an Atom `<feed>`.
+    pub fn parse(xml_document: &'a str) -> Result<Self, Error> {
+        Self::parse_with_options(xml_document, ParseOptions::default())
+    }
+    /// Parses `xml_document` with `options`.
+    ///
+    /// See [`crate::Document::parse_with_options`].
+    pub fn parse_with_options(xml_document: &'a str, options: ParseOptions) -> Result<Self, Error> {
+        check_size(xml_document, &options)?;
+        let doc = roxmltree::Document::parse(xml_document).map_err(Error::Parse)?;
+        let root = doc.root_element();
+        let kind = match (root.tag_name().namespace(), root.tag_name().name()) {
+            (None, "rss") => FeedKind::Rss,
+            (Some(ATOM_NAMESPACE), "feed") | (Some(ATOM_0_3_NAMESPACE), "feed") => FeedKind::Atom,
+            _ => return Err(Error::FeedMissing),
+        };
+        let mut me = Self {
+            doc,
+            lines: LineIndex::new(xml_document),
+            kind,
+            dates: Vec::new(),
+            options,
+        };
+        if kind == FeedKind::Rss {
+            me.dates = me
+                .items()
+                .into_iter()
+                .map(|item| {
+                    let date = child(item, None, "pubDate").and_then(|date| date.text())?;
+                    W3cDatetime::parse_rfc822(date)
+                        .ok()
+                        .map(|date| date.to_string())
+                })
+                .collect();
+        }
+        Ok(me)
+    }
+    /// Parses `bytes`, which must be UTF-8.
+    ///
+    /// See [`crate::Document::from_bytes`] for other encodings.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        from_utf8(bytes).and_then(Self::parse)
+    }
+    /// Set how problems in the entries are handled.
+    ///
+    /// Only the rules about the location, the last modification, and the limits apply.
+    pub fn options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+    /// Returns the format of this feed.
+    pub fn kind(&self) -> FeedKind {
+        self.kind
+    }
+    /// Returns an iterator of [`UrlEntry`].
+    ///
+    /// Uses [`log`] for logging errors in the XML.
+    /// See [`Feed::iterate_with_diagnostics`] to get them as data.
+    pub fn iterate(
+        &'a self,
+    ) -> Result<impl DoubleEndedIterator<Item = UrlEntry<'a>> + Clone + Debug + 'a, Error> {
+        self.iterate_with_diagnostics()
+            .map(|iter| iter.filter_map(Report::log))
+    }
+    /// Returns an iterator of a [`Report`] for every item of the feed.
+    ///
+    /// See [`crate::Document::iterate_with_diagnostics`] for how [`Error::Rejected`] is returned.
+    pub fn iterate_with_diagnostics(
+        &'a self,
+    ) -> Result<
+        impl DoubleEndedIterator<Item = Report<UrlEntry<'a>>> + ExactSizeIterator + Clone + Debug + 'a,
+        Error,
+    > {
+        check_size(self.doc.input_text(), &self.options)?;
+        let reports = self.reports();
+        check_fatal(&self.options, reports.clone())?;
+        Ok(reports)
+    }
+    /// Returns all the problems found in the items of this feed.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.reports()
+            .flat_map(|report| report.diagnostics)
+            .collect()
+    }
+
+    fn reports(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Report<UrlEntry<'_>>> + ExactSizeIterator + Clone + Debug + '_
+    {
+        self.items()
+            .into_iter()
+            .take(self.options.max_entries.unwrap_or(usize::MAX))
+            .enumerate()
+            .map(move |(index, item)| {
+                let mut report = self.parse_item(index, item);
+                check_limits(
+                    &mut report,
+                    index,
+                    || self.position(item),
+                    item.range().end as u64,
+                );
+                report
+            })
+    }
+    /// The `<item>`s of the `<channel>` or the `<entry>`s of the `<feed>`.
+    fn items(&self) -> Vec<Node<'_, 'a>> {
+        let root = self.doc.root_element();
+        match self.kind {
+            FeedKind::Rss => child(root, None, "channel")
+                .map(|channel| children(channel, None, "item").collect())
+                .unwrap_or_default(),
+            FeedKind::Atom => children(root, root.tag_name().namespace(), "entry").collect(),
+        }
+    }
+    /// The position of the start of `node`.
+    fn position(&self, node: Node<'_, '_>) -> TextPos {
+        self.lines
+            .position(self.doc.input_text(), node.range().start)
+    }
+    fn parse_item<'s>(&'s self, index: usize, item: Node<'s, 'a>) -> Report<UrlEntry<'s>> {
+        let options = &self.options;
+        let mut diagnostics = Vec::new();
+        let mut diagnostic = |node: Node, kind| {
+            diagnostics.push(Diagnostic {
+                entry: index,
+                position: self.position(node),
+                severity: options.severity(&kind),
+                kind,
+            })
+        };
+        let namespace = item.tag_name().namespace();
+        let (link, location, date) = match self.kind {
+            FeedKind::Rss => {
+                let link = child(item, None, "link");
+                (link, link.and_then(|link| link.text()), "pubDate")
+            }
+            FeedKind::Atom => {
+                let link = children(item, namespace, "link").find(|link| {
+                    matches!(link.attribute("rel"), None | Some("alternate"))
+                        && link.has_attribute("href")
+                });
+                let date = if namespace == Some(ATOM_0_3_NAMESPACE) {
+                    "modified"
+                } else {
+                    "updated"
+                };
+                (link, link.and_then(|link| link.attribute("href")), date)
+            }
+        };
+        match (link, location) {
+            (Some(link), Some(location)) => {
+                for kind in location_problems(location) {
+                    diagnostic(link, kind);
+                }
+            }
+            _ => diagnostic(item, DiagnosticKind::MissingLocation),
+        }
+        let mut last_modified = None;
+        if let Some(node) = child(item, namespace, date) {
+            let text = node.text().unwrap_or_default();
+            let converted = match self.kind {
+                FeedKind::Rss => self.dates[index].as_deref(),
+                FeedKind::Atom => Some(text).filter(|text| W3cDatetime::parse(text).is_ok()),
+            };
+            last_modified = converted.or_else(|| {
+                diagnostic(node, DiagnosticKind::InvalidLastModified(text.to_owned()));
+                Some(text).filter(|_| options.invalid_last_modified == InvalidPolicy::Keep)
+            });
+        }
+        let skipped = diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity >= Severity::Error);
+        let entry = location.filter(|_| !skipped).map(|location| UrlEntry {
+            last_modified,
+            ..UrlEntry::new(location)
+        });
+        Report {
+            index,
+            entry,
+            diagnostics,
+        }
+    }
+}
+
+/// The child elements of `node` named `name` in `namespace`.
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    namespace: Option<&'a str>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(move |child| {
+        child.is_element()
+            && child.tag_name().namespace() == namespace
+            && child.tag_name().name() == name
+    })
+}
+/// The first child element of `node` named `name` in `namespace`.
+fn child<'a, 'input>(
+    node: Node<'a, 'input>,
+    namespace: Option<&'a str>,
+    name: &'a str,
+) -> Option<Node<'a, 'input>> {
+    children(node, namespace, name).next()
+}
@@ -0,0 +1,144 @@
+//! Transparent decompression of gzipped sitemaps (`sitemap.xml.gz`).
+//!
+//! The input is only decompressed if it starts with the gzip magic bytes,
+//! so these functions can be used on all input.
+//! Concatenated gzip members (e.g. from `cat a.gz b.gz`) are decompressed as one.
+//!
+//! The decompressed size is capped to protect against zip bombs.
+
+use flate2::bufread::MultiGzDecoder;
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, BufReader, Read};
+
+/// The first bytes of all gzip data.
+pub const MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// The maximum size of a uncompressed sitemap, according to the spec.
+///
+/// A good default for the `max_size` parameters in this module.
+pub const DEFAULT_MAX_SIZE: u64 = 50 * 1024 * 1024;
+
+/// The decompressed data exceeds the `max_size`.
+///
+/// When using [`MaybeGzip`], this is returned as the inner error of a
+/// [`io::Error`] with [`io::ErrorKind::InvalidData`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TooLarge {
+    pub max_size: u64,
+}
+impl Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "decompressed sitemap exceeds the maximum size of {} bytes",
+            self.max_size
+        )
+    }
+}
+impl std::error::Error for TooLarge {}
+
+/// Returns `true` if `bytes` starts with the gzip [`MAGIC`].
+pub fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.starts_with(&MAGIC)
+}
+
+/// Decompresses `bytes` if they are gzipped, otherwise returns them as-is.
+///
+/// Returns an error if the data is corrupt or the decompressed data exceeds `max_size`.
+/// In the latter case, the error contains [`TooLarge`].
+pub fn decompress(bytes: &[u8], max_size: u64) -> io::Result<Cow<'_, [u8]>> {
+    if !is_gzip(bytes) {
+        return Ok(Cow::Borrowed(bytes));
+    }
+    let mut decompressed = Vec::new();
+    Limit::new(MultiGzDecoder::new(bytes), max_size).read_to_end(&mut decompressed)?;
+    Ok(Cow::Owned(decompressed))
+}
+
+/// Errors when more than `max_size` bytes are read.
+#[derive(Debug)]
+struct Limit<R> {
+    reader: R,
+    read: u64,
+    max_size: u64,
+}
+impl<R> Limit<R> {
+    fn new(reader: R, max_size: u64) -> Self {
+        Self {
+            reader,
+            read: 0,
+            max_size,
+        }
+    }
+}
+impl<R: Read> Read for Limit<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.read += read as u64;
+        if self.read > self.max_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                TooLarge {
+                    max_size: self.max_size,
+                },
+            ));
+        }
+        Ok(read)
+    }
+}
+
+/// A [`BufRead`] which decompresses the inner reader if it's gzipped.
+///
+/// Use this with [`crate::StreamParser::new`] to parse a sitemap which might be gzipped.
+#[derive(Debug)]
+pub struct MaybeGzip<R> {
+    inner: Inner<R>,
+}
+#[derive(Debug)]
+enum Inner<R> {
+    Plain(R),
+    Gzip(Box<BufReader<Limit<MultiGzDecoder<R>>>>),
+}
+impl<R: BufRead> MaybeGzip<R> {
+    /// Sniffs the first bytes of `reader` for the gzip [`MAGIC`].
+    ///
+    /// If it's gzipped, reading more than `max_size` decompressed bytes
+    /// results in an error containing [`TooLarge`].
+    pub fn new(mut reader: R, max_size: u64) -> io::Result<Self> {
+        let inner = if is_gzip(reader.fill_buf()?) {
+            Inner::Gzip(Box::new(BufReader::new(Limit::new(
+                MultiGzDecoder::new(reader),
+                max_size,
+            ))))
+        } else {
+            Inner::Plain(reader)
+        };
+        Ok(Self { inner })
+    }
+    /// Returns `true` if the inner reader is gzipped.
+    pub fn is_gzip(&self) -> bool {
+        matches!(self.inner, Inner::Gzip(_))
+    }
+}
+impl<R: BufRead> Read for MaybeGzip<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            Inner::Plain(reader) => reader.read(buf),
+            Inner::Gzip(reader) => reader.read(buf),
+        }
+    }
+}
+impl<R: BufRead> BufRead for MaybeGzip<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match &mut self.inner {
+            Inner::Plain(reader) => reader.fill_buf(),
+            Inner::Gzip(reader) => reader.fill_buf(),
+        }
+    }
+    fn consume(&mut self, amt: usize) {
+        match &mut self.inner {
+            Inner::Plain(reader) => reader.consume(amt),
+            Inner::Gzip(reader) => reader.consume(amt),
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! Consistency checks of [language alternates](crate::xhtml) across sitemaps.
+//!
+//! Pages which link to each other through their [`crate::UrlEntry::alternates`]
+//! form a [`Cluster`]. Add the entries of one or more sitemaps to a [`Validator`]
+//! and call [`Validator::validate`] to get the [`Issue`]s of each cluster.
+//!
+//! URLs are compared as-is; normalize them before adding the entries if needed.
+
+use crate::news::ISO_639_1;
+use crate::xhtml::X_DEFAULT;
+use crate::{OwnedAlternate, UrlEntry};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+/// The [ISO 3166-1 alpha-2](https://en.wikipedia.org/wiki/ISO_3166-1_alpha-2) codes, sorted.
+const ISO_3166_1: &[&str] = &[
+    "ad", "ae", "af", "ag", "ai", "al", "am", "ao", "aq", "ar", "as", "at", "au", "aw", "ax", "az",
+    "ba", "bb", "bd", "be", "bf", "bg", "bh", "bi", "bj", "bl", "bm", "bn", "bo", "bq", "br", "bs",
+    "bt", "bv", "bw", "by", "bz", "ca", "cc", "cd", "cf", "cg", "ch", "ci", "ck", "cl", "cm", "cn",
+    "co", "cr", "cu", "cv", "cw", "cx", "cy", "cz", "de", "dj", "dk", "dm", "do", "dz", "ec", "ee",
+    "eg", "eh", "er", "es", "et", "fi", "fj", "fk", "fm", "fo", "fr", "ga", "gb", "gd", "ge", "gf",
+    "gg", "gh", "gi", "gl", "gm", "gn", "gp", "gq", "gr", "gs", "gt", "gu", "gw", "gy", "hk", "hm",
+    "hn", "hr", "ht", "hu", "id", "ie", "il", "im", "in", "io", "iq", "ir", "is", "it", "je", "jm",
+    "jo", "jp", "ke", "kg", "kh", "ki", "km", "kn", "kp", "kr", "kw", "ky", "kz", "la", "lb", "lc",
+    "li", "lk", "lr", "ls", "lt", "lu", "lv", "ly", "ma", "mc", "md", "me", "mf", "mg", "mh", "mk",
+    "ml", "mm", "mn", "mo", "mp", "mq", "mr", "ms", "mt", "mu", "mv", "mw", "mx", "my", "mz", "na",
+    "nc", "ne", "nf", "ng", "ni", "nl", "no", "np", "nr", "nu", "nz", "om", "pa", "pe", "pf", "pg",
+    "ph", "pk", "pl", "pm", "pn", "pr", "ps", "pt", "pw", "py", "qa", "re", "ro", "rs", "ru", "rw",
+    "sa", "sb", "sc", "sd", "se", "sg", "sh", "si", "sj", "sk", "sl", "sm", "sn", "so", "sr", "ss",
+    "st", "sv", "sx", "sy", "sz", "tc", "td", "tf", "tg", "th", "tj", "tk", "tl", "tm", "tn", "to",
+    "tr", "tt", "tv", "tw", "tz", "ua", "ug", "um", "us", "uy", "uz", "va", "vc", "ve", "vg", "vi",
+    "vn", "vu", "wf", "ws", "ye", "yt", "za", "zm", "zw",
+];
+
+/// Check if `hreflang` is a valid value of [`crate::Alternate::hreflang`].
+///
+/// That is `x-default`, or a [BCP 47](https://www.rfc-editor.org/info/bcp47) tag
+/// of the form `language[-script][-region]`, case-insensitively.
+/// Two-letter languages must be [ISO 639-1](https://en.wikipedia.org/wiki/List_of_ISO_639-1_codes)
+/// and two-letter regions [ISO 3166-1 alpha-2](https://en.wikipedia.org/wiki/ISO_3166-1_alpha-2)
+/// (e.g. `en-uk` is invalid, as the code of the United Kingdom is `gb`).
+/// Three-letter languages and three-digit regions (e.g. `es-419`) are only checked for format.
+pub fn is_valid_hreflang(hreflang: &str) -> bool {
+    if hreflang.eq_ignore_ascii_case(X_DEFAULT) {
+        return true;
+    }
+    let hreflang = hreflang.to_ascii_lowercase();
+    let mut subtags = hreflang.split('-').peekable();
+    let language = subtags.next().unwrap_or_default();
+    let valid_language = match language.len() {
+        2 => ISO_639_1.binary_search(&language).is_ok(),
+        3 => language.bytes().all(|b| b.is_ascii_alphabetic()),
+        _ => false,
+    };
+    if !valid_language {
+        return false;
+    }
+    if let Some(script) = subtags.peek() {
+        if script.len() == 4 {
+            if !script.bytes().all(|b| b.is_ascii_alphabetic()) {
+                return false;
+            }
+            subtags.next();
+        }
+    }
+    if let Some(region) = subtags.next() {
+        let valid_region = match region.len() {
+            2 => ISO_3166_1.binary_search(&region).is_ok(),
+            3 => region.bytes().all(|b| b.is_ascii_digit()),
+            _ => false,
+        };
+        if !valid_region {
+            return false;
+        }
+    }
+    subtags.next().is_none()
+}
+
+/// A problem with the alternates of a [`Cluster`].
+#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
+pub enum Issue {
+    /// `from` lists `to` as an alternate, but `to` doesn't list `from`.
+    MissingReturnLink { from: String, to: String },
+    /// `page` has alternates, but doesn't list itself.
+    MissingSelfReference { page: String },
+    /// `page` lists `href`, which isn't an entry of the added sitemaps,
+    /// so its return links can't be checked.
+    UnknownAlternate { page: String, href: String },
+    /// `page` lists an alternate with an [invalid](is_valid_hreflang) `hreflang`.
+    InvalidHreflang { page: String, hreflang: String },
+    /// Several URLs are listed for the same `hreflang` in the cluster.
+    ///
+    /// `hreflang` is lowercase.
+    DuplicateHreflang {
+        hreflang: String,
+        hrefs: Vec<String>,
+    },
+    /// No page of the cluster lists an `x-default` alternate.
+    MissingDefault,
+}
+impl Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingReturnLink { from, to } => {
+                write!(
+                    f,
+                    "{from} lists {to} as an alternate, but not the other way around."
+                )
+            }
+            Self::MissingSelfReference { page } => {
+                write!(f, "{page} doesn't list itself as an alternate.")
+            }
+            Self::UnknownAlternate { page, href } => write!(
+                f,
+                "{page} lists {href} as an alternate, which isn't in the sitemaps."
+            ),
+            Self::InvalidHreflang { page, hreflang } => {
+                write!(
+                    f,
+                    "{page} has an alternate with invalid hreflang {hreflang:?}."
+                )
+            }
+            Self::DuplicateHreflang { hreflang, hrefs } => {
+                write!(f, "Multiple URLs for hreflang {hreflang:?}: {hrefs:?}")
+            }
+            Self::MissingDefault => f.write_str("No x-default alternate in cluster."),
+        }
+    }
+}
+
+/// A group of pages which are alternates of each other.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Cluster {
+    /// The URLs in the cluster, both entries and alternates, sorted.
+    pub pages: Vec<String>,
+    pub issues: Vec<Issue>,
+}
+impl Cluster {
+    /// Check if the cluster has no [`Cluster::issues`].
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Collects the alternates of entries and checks their consistency.
+///
+/// The alternates are copied, so entries from a [`crate::StreamParser`] can be added.
+#[derive(Debug, Default, Clone)]
+pub struct Validator {
+    /// The alternates of each entry.
+    pages: BTreeMap<String, Vec<OwnedAlternate>>,
+}
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add the alternates of `entry`.
+    ///
+    /// If the same location is added multiple times, the alternates are merged.
+    pub fn add(&mut self, entry: &UrlEntry<'_>) {
+        let alternates = self
+            .pages
+            .entry(entry.location.trim().to_owned())
+            .or_default();
+        alternates.extend(entry.alternates.iter().copied().map(OwnedAlternate::from));
+    }
+    /// Check all the clusters of the added entries.
+    ///
+    /// Entries without alternates which no other entry lists are not part of any cluster.
+    pub fn validate(&self) -> Vec<Cluster> {
+        let mut clusters = UnionFind::default();
+        for (page, alternates) in &self.pages {
+            let page = clusters.id(page);
+            for alternate in alternates {
+                let href = clusters.id(&alternate.href);
+                clusters.union(page, href);
+            }
+        }
+        let mut pages: BTreeMap<usize, BTreeSet<&str>> = BTreeMap::new();
+        let ids: Vec<_> = clusters.ids.iter().map(|(url, id)| (*url, *id)).collect();
+        for (url, id) in ids {
+            pages.entry(clusters.find(id)).or_default().insert(url);
+        }
+        let mut clusters: Vec<_> = pages
+            .into_values()
+            .filter(|pages| {
+                pages.len() > 1
+                    || pages
+                        .iter()
+                        .any(|page| self.pages.get(*page).map_or(false, |a| !a.is_empty()))
+            })
+            .map(|pages| self.check(pages))
+            .collect();
+        clusters.sort_by(|a, b| a.pages.cmp(&b.pages));
+        clusters
+    }
+    fn check(&self, pages: BTreeSet<&str>) -> Cluster {
+        let mut issues = Vec::new();
+        let mut hreflangs: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+        for &page in &pages {
+            let alternates = match self.pages.get(page) {
+                Some(alternates) if !alternates.is_empty() => alternates,
+                _ => continue,
+            };
+            if !alternates.iter().any(|alternate| alternate.href == page) {
+                issues.push(Issue::MissingSelfReference {
+                    page: page.to_owned(),
+                });
+            }
+            for alternate in alternates {
+                hreflangs
+                    .entry(alternate.hreflang.to_ascii_lowercase())
+                    .or_default()
+                    .insert(&alternate.href);
+                if !is_valid_hreflang(&alternate.hreflang) {
+                    issues.push(Issue::InvalidHreflang {
+                        page: page.to_owned(),
+                        hreflang: alternate.hreflang.clone(),
+                    });
+                }
+                if alternate.href == page {
+                    continue;
+                }
+                match self.pages.get(&alternate.href) {
+                    Some(others) => {
+                        if !others.iter().any(|other| other.href == page) {
+                            issues.push(Issue::MissingReturnLink {
+                                from: page.to_owned(),
+                                to: alternate.href.clone(),
+                            });
+                        }
+                    }
+                    None => issues.push(Issue::UnknownAlternate {
+                        page: page.to_owned(),
+                        href: alternate.href.clone(),
+                    }),
+                }
+            }
+        }
+        if !hreflangs.contains_key(X_DEFAULT) {
+            issues.push(Issue::MissingDefault);
+        }
+        for (hreflang, hrefs) in hreflangs {
+            if hrefs.len() > 1 {
+                issues.push(Issue::DuplicateHreflang {
+                    hreflang,
+                    hrefs: hrefs.into_iter().map(str::to_owned).collect(),
+                });
+            }
+        }
+        issues.sort();
+        issues.dedup();
+        Cluster {
+            pages: pages.into_iter().map(str::to_owned).collect(),
+            issues,
+        }
+    }
+}
+
+/// Disjoint sets of URLs.
+#[derive(Debug, Default)]
+struct UnionFind<'a> {
+    ids: BTreeMap<&'a str, usize>,
+    parents: Vec<usize>,
+}
+impl<'a> UnionFind<'a> {
+    fn id(&mut self, url: &'a str) -> usize {
+        let parents = &mut self.parents;
+        *self.ids.entry(url).or_insert_with(|| {
+            parents.push(parents.len());
+            parents.len() - 1
+        })
+    }
+    fn find(&mut self, mut id: usize) -> usize {
+        while self.parents[id] != id {
+            self.parents[id] = self.parents[self.parents[id]];
+            id = self.parents[id];
+        }
+        id
+    }
+    fn union(&mut self, a: usize, b: usize) {
+        let a = self.find(a);
+        let b = self.find(b);
+        self.parents[a] = b;
+    }
+}
@@ -0,0 +1,125 @@
+//! The [image sitemap extension](https://developers.google.com/search/docs/crawling-indexing/sitemaps/image-sitemaps).
+//!
+//! Images are available in [`crate::UrlEntry::images`].
+
+use crate::parse::Element;
+use crate::DiagnosticKind;
+
+/// The XML namespace of the image extension.
+pub const NAMESPACE: &str = "http://www.google.com/schemas/sitemap-image/1.1";
+
+/// An image on the page of a [`crate::UrlEntry`].
+///
+/// `<image:image>`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Image<'a> {
+    /// The URL of the image.
+    ///
+    /// `<image:loc>`
+    pub location: &'a str,
+    /// `<image:caption>`
+    ///
+    /// Deprecated by Google.
+    pub caption: Option<&'a str>,
+    /// `<image:title>`
+    ///
+    /// Deprecated by Google.
+    pub title: Option<&'a str>,
+    /// `<image:geo_location>`
+    ///
+    /// Deprecated by Google.
+    pub geo_location: Option<&'a str>,
+    /// The URL of the license of the image.
+    ///
+    /// `<image:license>`
+    ///
+    /// Deprecated by Google.
+    pub license: Option<&'a str>,
+}
+/// An owned version of [`Image`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OwnedImage {
+    /// See [`Image::location`].
+    pub location: String,
+    /// See [`Image::caption`].
+    pub caption: Option<String>,
+    /// See [`Image::title`].
+    pub title: Option<String>,
+    /// See [`Image::geo_location`].
+    pub geo_location: Option<String>,
+    /// See [`Image::license`].
+    pub license: Option<String>,
+}
+impl OwnedImage {
+    /// Borrow this as a [`Image`].
+    pub fn as_image(&self) -> Image<'_> {
+        Image {
+            location: &self.location,
+            caption: self.caption.as_deref(),
+            title: self.title.as_deref(),
+            geo_location: self.geo_location.as_deref(),
+            license: self.license.as_deref(),
+        }
+    }
+}
+impl<'a> From<Image<'a>> for OwnedImage {
+    fn from(image: Image<'a>) -> Self {
+        Self {
+            location: image.location.to_owned(),
+            caption: image.caption.map(str::to_owned),
+            title: image.title.map(str::to_owned),
+            geo_location: image.geo_location.map(str::to_owned),
+            license: image.license.map(str::to_owned),
+        }
+    }
+}
+
+/// Parses a `<image:image>`. Returns [`None`] if it has no `<image:loc>`.
+pub(crate) fn parse<'a, E: Element<'a>>(
+    node: E,
+    diagnostic: &mut impl FnMut(E, DiagnosticKind),
+) -> Option<Image<'a>> {
+    let mut location = None;
+    let mut caption = None;
+    let mut title = None;
+    let mut geo_location = None;
+    let mut license = None;
+    for child in node.children() {
+        if child.namespace() != Some(NAMESPACE) {
+            continue;
+        }
+        let field = match child.name() {
+            "loc" => &mut location,
+            "caption" => &mut caption,
+            "title" => &mut title,
+            "geo_location" => &mut geo_location,
+            "license" => &mut license,
+            _ => continue,
+        };
+        if let Some(text) = child.text() {
+            if field.is_some() {
+                diagnostic(
+                    child,
+                    DiagnosticKind::DuplicateExtensionField(format!("image:{}", child.name())),
+                );
+            }
+            *field = Some(text);
+        }
+    }
+    if location.is_none() {
+        diagnostic(
+            node,
+            DiagnosticKind::MissingExtensionField {
+                element: "image:image".to_owned(),
+                field: "image:loc".to_owned(),
+            },
+        );
+    }
+    location.map(|location| Image {
+        location,
+        caption,
+        title,
+        geo_location,
+        license,
+    })
+}
@@ -1,7 +1,63 @@
-use log::{error, warn};
-use std::fmt::Debug;
+use log::error;
+use std::fmt::{self, Debug, Display};
 use std::str::FromStr;
 
+mod datetime;
+pub mod detect;
+mod diagnostic;
+#[cfg(feature = "encoding_rs")]
+pub mod encoding;
+mod extension;
+pub mod feed;
+#[cfg(feature = "gzip")]
+pub mod gzip;
+pub mod hreflang;
+pub mod image;
+#[cfg(feature = "url")]
+pub mod location;
+pub mod news;
+mod options;
+mod owned;
+mod parse;
+pub mod robots;
+pub mod schema;
+#[cfg(feature = "url")]
+pub mod scope;
+pub mod stream;
+pub mod text;
+pub mod video;
+pub mod writer;
+pub mod xhtml;
+
+pub use datetime::{DatetimeParseError, Precision, W3cDatetime};
+pub use detect::Sitemap;
+pub use diagnostic::{Diagnostic, DiagnosticKind, Report, Severity, TextPos};
+pub use extension::{
+    ExtensionAttribute, ExtensionElement, OwnedExtensionAttribute, OwnedExtensionElement,
+};
+pub use feed::Feed;
+pub use image::{Image, OwnedImage};
+pub use news::{NewsInfo, OwnedNewsInfo};
+pub use options::{DuplicatePolicy, InvalidPolicy, ParseOptions, RangePolicy};
+pub use owned::{OwnedDocument, OwnedSitemapEntry, OwnedUrlEntry};
+pub use stream::StreamParser;
+pub use text::TextSitemap;
+pub use video::{OwnedVideo, Video};
+pub use writer::{SitemapIndexWriter, SitemapWriter, SplittingWriter};
+pub use xhtml::{Alternate, OwnedAlternate};
+
+/// The XML namespace of sitemaps, as defined in the [spec](https://sitemaps.org/protocol.html).
+pub const NAMESPACE: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+/// The namespace of Google's sitemaps, before the [`NAMESPACE`] of the spec.
+///
+/// Accepted by [`NamespaceMode::StrictWithLegacy`].
+pub const LEGACY_NAMESPACE: &str = "http://www.google.com/schemas/sitemap/0.84";
+
+use parse::{
+    check_limits, check_news_limit, parse_sitemap_entry, parse_url_entry, DomNode, Element,
+    LineIndex,
+};
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum FrequencyParseError {
     InvalidFrequency,
@@ -39,22 +95,45 @@ impl FromStr for Frequency {
         })
     }
 }
+impl Frequency {
+    /// Get the value of `<changefreq>` for this frequency, as defined in the spec.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+            Self::Never => "never",
+        }
+    }
+}
+impl Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 /// The data of a entry in the `urlset`.
 ///
 /// See the [official spec](https://sitemaps.org/protocol.html) for more details.
-#[derive(Debug, PartialEq, Clone, Copy)]
+///
+/// More fields may be added, so build entries with [`UrlEntry::new`].
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
 pub struct UrlEntry<'a> {
     /// The location of this entry.
     ///
     /// `<loc>`
     ///
-    /// I recommend using `http::Uri` to parse this, then extract the `Uri::path()`.
+    /// This is the text as-is. With the `url` feature, see `UrlEntry::location_url`.
     pub location: &'a str,
     /// The date of last modification.
     ///
     /// `<lastmod>`
     ///
     /// Format should be in [W3C Datetime](https://www.w3.org/TR/NOTE-datetime).
+    /// See [`UrlEntry::last_modified_datetime`].
     pub last_modified: Option<&'a str>,
     /// The frequency of change in this resource.
     ///
@@ -66,112 +145,499 @@ pub struct UrlEntry<'a> {
     ///
     /// Ranges from `0.0` to `1.0`
     pub priority: Option<f32>,
+    /// The images on this page, from the [image extension](image).
+    ///
+    /// `<image:image>`
+    pub images: Vec<Image<'a>>,
+    /// The videos on this page, from the [video extension](video).
+    ///
+    /// `<video:video>`
+    pub videos: Vec<Video<'a>>,
+    /// The news article on this page, from the [news extension](news).
+    ///
+    /// `<news:news>`
+    pub news: Option<NewsInfo<'a>>,
+    /// The versions of this page in other languages, from [`<xhtml:link rel="alternate">`](xhtml).
+    pub alternates: Vec<Alternate<'a>>,
+    /// The child elements which aren't recognized, e.g. from other extensions
+    /// or `<xhtml:link>` which aren't [`UrlEntry::alternates`].
+    pub extensions: Vec<ExtensionElement<'a>>,
+}
+impl<'a> UrlEntry<'a> {
+    /// An entry with only a `location`; set the other fields as needed.
+    ///
+    /// ```
+    /// # use sitemap_iter::{Frequency, UrlEntry};
+    /// let mut entry = UrlEntry::new("https://example.com/");
+    /// entry.change_frequency = Some(Frequency::Daily);
+    /// ```
+    pub fn new(location: &'a str) -> Self {
+        Self {
+            location,
+            last_modified: None,
+            change_frequency: None,
+            priority: None,
+            images: Vec::new(),
+            videos: Vec::new(),
+            news: None,
+            alternates: Vec::new(),
+            extensions: Vec::new(),
+        }
+    }
+    /// Parses [`UrlEntry::last_modified`].
+    ///
+    /// Returns [`None`] if there's no `<lastmod>`.
+    pub fn last_modified_datetime(&self) -> Option<Result<W3cDatetime, DatetimeParseError>> {
+        self.last_modified.map(W3cDatetime::parse)
+    }
+    /// Parses [`UrlEntry::location`] using [`location::parse`].
+    #[cfg(feature = "url")]
+    pub fn location_url(&self) -> Result<location::Url, location::LocationError> {
+        location::parse(self.location)
+    }
+}
+/// The data of a entry in the `sitemapindex`.
+///
+/// Each entry points to another sitemap.
+/// See the [official spec](https://sitemaps.org/protocol.html#index) for more details.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SitemapEntry<'a> {
+    /// The location of the referenced sitemap.
+    ///
+    /// `<loc>`
+    pub location: &'a str,
+    /// The date of last modification of the referenced sitemap.
+    ///
+    /// `<lastmod>`
+    ///
+    /// Format should be in [W3C Datetime](https://www.w3.org/TR/NOTE-datetime).
+    /// See [`SitemapEntry::last_modified_datetime`].
+    pub last_modified: Option<&'a str>,
+}
+impl<'a> SitemapEntry<'a> {
+    /// Parses [`SitemapEntry::last_modified`].
+    ///
+    /// Returns [`None`] if there's no `<lastmod>`.
+    pub fn last_modified_datetime(&self) -> Option<Result<W3cDatetime, DatetimeParseError>> {
+        self.last_modified.map(W3cDatetime::parse)
+    }
+    /// Parses [`SitemapEntry::location`] using [`location::parse`].
+    #[cfg(feature = "url")]
+    pub fn location_url(&self) -> Result<location::Url, location::LocationError> {
+        location::parse(self.location)
+    }
+}
+/// The kind of the root element of a [`Document`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DocumentKind {
+    /// A `<urlset>`, containing [`UrlEntry`]s.
+    ///
+    /// Use [`Document::iterate`].
+    Urlset,
+    /// A `<sitemapindex>`, containing [`SitemapEntry`]s.
+    ///
+    /// Use [`Document::iterate_sitemaps`].
+    SitemapIndex,
+}
+/// How the namespaces of the root element, entries, and their fields are checked.
+///
+/// Extensions (e.g. [`image`]) are always matched by their namespace.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NamespaceMode {
+    /// Namespaces are ignored; only the local names are compared.
+    ///
+    /// This is the default.
+    Lenient,
+    /// The elements must be in [`NAMESPACE`].
+    ///
+    /// A root element in another namespace gives [`Error::UnexpectedNamespace`].
+    /// Entries and fields (e.g. `<foo:loc>`) in another namespace get a
+    /// [`DiagnosticKind::UnexpectedNamespace`]; such fields are
+    /// treated as [`UrlEntry::extensions`].
+    Strict,
+    /// Like [`NamespaceMode::Strict`], but also accepts [`LEGACY_NAMESPACE`].
+    StrictWithLegacy,
+}
+impl NamespaceMode {
+    /// Check if an element in `namespace` is accepted as part of the sitemap protocol.
+    pub fn accepts(&self, namespace: Option<&str>) -> bool {
+        match self {
+            Self::Lenient => true,
+            Self::Strict => namespace == Some(NAMESPACE),
+            Self::StrictWithLegacy => {
+                namespace == Some(NAMESPACE) || namespace == Some(LEGACY_NAMESPACE)
+            }
+        }
+    }
+}
+impl Default for NamespaceMode {
+    fn default() -> Self {
+        Self::Lenient
+    }
 }
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Error {
     /// The mandatory `<urlset>` tag is missing.
     ///
-    /// You maybe don't have a sitemap.
+    /// You maybe don't have a sitemap, or it's a sitemap index.
+    /// See [`Document::kind`].
     UrlsetMissing,
+    /// The mandatory `<sitemapindex>` tag is missing.
+    ///
+    /// You maybe don't have a sitemap index, or it's a regular sitemap.
+    /// See [`Document::kind`].
+    SitemapIndexMissing,
+    /// The root element is neither a RSS `<rss>` nor an Atom `<feed>`.
+    ///
+    /// See [`feed::Feed::parse`].
+    FeedMissing,
     Parse(roxmltree::Error),
+    /// The input isn't valid UTF-8.
+    Utf8(std::str::Utf8Error),
+    /// The byte order mark or XML declaration of the input gives an encoding other than UTF-8.
+    ///
+    /// Contains the encoding, as declared. With the `encoding_rs` feature,
+    /// transcode the input with `encoding::decode` first, or use [`OwnedDocument::from_bytes`].
+    UnsupportedEncoding(String),
+    /// The root element isn't in a namespace accepted by the [`NamespaceMode`].
+    ///
+    /// Contains the namespace found.
+    UnexpectedNamespace(Option<String>),
+    /// An entry has a problem with [`Severity::Fatal`], as set by the [`ParseOptions`].
+    Rejected(Diagnostic),
+    /// The document is larger than the [`ParseOptions::max_size`].
+    ///
+    /// Contains the limit.
+    TooLarge(u64),
+    /// The input is an HTML page, e.g. an error page or a "soft 404" served instead of the sitemap.
+    ///
+    /// See [`detect::Sitemap::parse`].
+    Html,
+    /// The input is gzipped; decompress it first, e.g. with `gzip::decompress`
+    /// or `detect::Sitemap::parse_with_buffer`.
+    ///
+    /// See [`detect::Sitemap::parse`].
+    Gzipped,
+    /// The input is neither a sitemap, sitemap index or feed: other XML,
+    /// or text which doesn't start with a URL.
+    ///
+    /// See [`detect::Sitemap::parse`].
+    UnknownFormat,
+    /// The input is empty, or only whitespace.
+    ///
+    /// See [`detect::Sitemap::parse`].
+    Empty,
+    /// The gzipped input is corrupt. Contains the message of the decompression error.
+    ///
+    /// See `detect::Sitemap::parse_with_buffer`.
+    InvalidGzip(String),
 }
 pub struct Document<'a> {
     doc: roxmltree::Document<'a>,
+    lines: LineIndex,
+    options: ParseOptions,
 }
 impl<'a> Document<'a> {
     /// Takes `xml_document` and parses it according to [the spec](https://sitemaps.org/protocol.html).
     pub fn parse(xml_document: &'a str) -> Result<Self, Error> {
+        Self::parse_with_options(xml_document, ParseOptions::default())
+    }
+    /// Parses `xml_document` with `options`.
+    ///
+    /// Unlike [`Document::options`], this returns [`Error::TooLarge`]
+    /// before parsing if `xml_document` exceeds the [`ParseOptions::max_size`].
+    pub fn parse_with_options(xml_document: &'a str, options: ParseOptions) -> Result<Self, Error> {
+        check_size(xml_document, &options)?;
         roxmltree::Document::parse(xml_document)
             .map_err(Error::Parse)
-            .map(|doc| Self { doc })
+            .map(|doc| Self {
+                doc,
+                lines: LineIndex::new(xml_document),
+                options,
+            })
+    }
+    /// Parses `bytes`, which must be UTF-8.
+    ///
+    /// Returns [`Error::UnsupportedEncoding`] if another encoding is declared
+    /// and the input isn't ASCII. With the `encoding_rs` feature, use `encoding::decode`
+    /// to transcode other encodings first.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        from_utf8(bytes).and_then(Self::parse)
+    }
+    /// Set how problems in the entries are handled.
+    pub fn options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+    /// Set how namespaces are checked.
+    ///
+    /// Shorthand for [`ParseOptions::namespace_mode`].
+    pub fn namespace_mode(mut self, namespace_mode: NamespaceMode) -> Self {
+        self.options.namespace_mode = namespace_mode;
+        self
+    }
+    /// Returns the namespace of the root element.
+    pub fn namespace(&self) -> Option<&str> {
+        DomNode::new(self.doc.root_element(), &self.lines).namespace()
+    }
+    /// Returns the kind of this document, based on the root element.
+    ///
+    /// Returns [`None`] if the root element is neither a `<urlset>` nor a `<sitemapindex>`,
+    /// or isn't in a namespace accepted by the [`NamespaceMode`].
+    pub fn kind(&self) -> Option<DocumentKind> {
+        if !self.options.namespace_mode.accepts(self.namespace()) {
+            return None;
+        }
+        match self.doc.root_element().tag_name().name() {
+            "urlset" => Some(DocumentKind::Urlset),
+            "sitemapindex" => Some(DocumentKind::SitemapIndex),
+            _ => None,
+        }
     }
     /// Returns an iterator of [`UrlEntry`].
     ///
     /// Uses [`log`] for logging errors in the XML.
+    /// See [`Document::iterate_with_diagnostics`] to get them as data.
     pub fn iterate(
         &'a self,
-    ) -> Result<impl Iterator<Item = UrlEntry<'a>> + DoubleEndedIterator + Clone + Debug + 'a, Error>
-    {
-        self.doc
-            .root()
-            .children()
-            .find(|c| c.is_element())
-            .and_then(|node| {
-                if node.tag_name().name() == "urlset" {
-                    Some(node)
-                } else {
-                    error!("Expected <urlset> but got {:?}", node);
-                    None
-                }
+    ) -> Result<impl DoubleEndedIterator<Item = UrlEntry<'a>> + Clone + Debug + 'a, Error> {
+        self.iterate_with_diagnostics()
+            .map(|iter| iter.filter_map(Report::log))
+    }
+    /// Returns an iterator of a [`Report`] for every `<url>` in the `<urlset>`.
+    ///
+    /// The [`Report::diagnostics`] contain all the problems found in the entry.
+    ///
+    /// If the [`ParseOptions`] can fail the document, all entries are checked first,
+    /// and [`Error::Rejected`] is returned on the first [`Severity::Fatal`] problem.
+    pub fn iterate_with_diagnostics(
+        &'a self,
+    ) -> Result<
+        impl DoubleEndedIterator<Item = Report<UrlEntry<'a>>> + ExactSizeIterator + Clone + Debug + 'a,
+        Error,
+    > {
+        let reports = self.url_reports()?;
+        check_fatal(&self.options, reports.clone())?;
+        Ok(reports)
+    }
+    /// Returns an iterator of [`SitemapEntry`].
+    ///
+    /// Use this when [`Document::kind`] is [`DocumentKind::SitemapIndex`].
+    ///
+    /// Uses [`log`] for logging errors in the XML.
+    /// See [`Document::iterate_sitemaps_with_diagnostics`] to get them as data.
+    pub fn iterate_sitemaps(
+        &'a self,
+    ) -> Result<impl DoubleEndedIterator<Item = SitemapEntry<'a>> + Clone + Debug + 'a, Error> {
+        self.iterate_sitemaps_with_diagnostics()
+            .map(|iter| iter.filter_map(Report::log))
+    }
+    /// Returns an iterator of a [`Report`] for every `<sitemap>` in the `<sitemapindex>`.
+    ///
+    /// The [`Report::diagnostics`] contain all the problems found in the entry.
+    ///
+    /// See [`Document::iterate_with_diagnostics`] for how [`Error::Rejected`] is returned.
+    pub fn iterate_sitemaps_with_diagnostics(
+        &'a self,
+    ) -> Result<
+        impl DoubleEndedIterator<Item = Report<SitemapEntry<'a>>>
+            + ExactSizeIterator
+            + Clone
+            + Debug
+            + 'a,
+        Error,
+    > {
+        let reports = self.sitemap_reports()?;
+        check_fatal(&self.options, reports.clone())?;
+        Ok(reports)
+    }
+    /// Checks this document against the XML Schemas published on sitemaps.org,
+    /// and returns all the [`schema::Violation`]s.
+    ///
+    /// This ignores the [`ParseOptions`]: the schemas require [`NAMESPACE`] and
+    /// reject problems which parsing recovers from. See [`schema`] for the rules.
+    pub fn validate_schema(&self) -> Vec<schema::Violation> {
+        schema::validate(&self.doc, &self.lines)
+    }
+    /// Returns all the problems found in the entries of this document,
+    /// whether it's a `<urlset>` or a `<sitemapindex>`.
+    ///
+    /// Returns an error if the entries can't be checked, like [`Document::iterate`],
+    /// but not [`Error::Rejected`]: the [`Severity::Fatal`] problems are included.
+    pub fn diagnostics(&self) -> Result<Vec<Diagnostic>, Error> {
+        Ok(match self.kind() {
+            Some(DocumentKind::SitemapIndex) => self
+                .sitemap_reports()?
+                .flat_map(|report| report.diagnostics)
+                .collect(),
+            _ => self
+                .url_reports()?
+                .flat_map(|report| report.diagnostics)
+                .collect(),
+        })
+    }
+    /// Parse the `<url>` entries, if the root is a `<urlset>`.
+    fn url_reports(
+        &'a self,
+    ) -> Result<
+        impl DoubleEndedIterator<Item = Report<UrlEntry<'a>>> + ExactSizeIterator + Clone + Debug + 'a,
+        Error,
+    > {
+        let options = &self.options;
+        self.entries("urlset").map(|entries| {
+            // The index of the first entry past the limit of news entries.
+            let news_limit = entries
+                .clone()
+                .filter(|(_, node)| news::contains_news(*node))
+                .nth(news::MAX_ENTRIES)
+                .map(|(index, _)| index);
+            entries.map(move |(index, node)| {
+                let mut report = parse_url_entry(index, node, options);
+                check_news_limit(
+                    &mut report,
+                    node,
+                    news_limit.map_or(false, |limit| index >= limit),
+                );
+                check_limits(
+                    &mut report,
+                    index,
+                    || node.position(),
+                    node.node.range().end as u64,
+                );
+                report
             })
-            .map(|node| {
-                node.children().filter_map(|c| {
-                    let children = c.children().filter(|c| c.is_element());
-                    let mut loc = None;
-                    let mut lastmod = None;
-                    let mut changefreq = None;
-                    let mut priority = None;
-                    for child in children {
-                        if let Some(text) = node_text_expected_name(&child, "loc") {
-                            if loc.is_none() {
-                                loc = Some(text);
-                            } else {
-                                error!("Multiple <loc> in entry.");
-                                return None;
-                            }
-                        } else if let Some(text) = node_text_expected_name(&child, "lastmod") {
-                            if lastmod.is_some() {
-                                warn!("Multiple <lastmod> in entry.");
-                            }
-                            lastmod = Some(text);
-                        } else if let Some(text) = node_text_expected_name(&child, "changefreq") {
-                            if changefreq.is_some() {
-                                warn!("Multiple <changefreq> in entry.");
-                            }
-                            if let Ok(frequency) = text.parse() {
-                                changefreq = Some(frequency);
-                            } else {
-                                warn!("<changefreq> has invalid format: {text:?}");
-                            }
-                        } else if let Some(text) = node_text_expected_name(&child, "priority") {
-                            if priority.is_some() {
-                                warn!("Multiple <priority> in entry.");
-                            }
-                            if let Ok(num) = text.parse() {
-                                if (0.0..=1.0).contains(&num) {
-                                    priority = Some(num)
-                                } else {
-                                    warn!("<priority> {num} is out of range",)
-                                }
-                            }else {
-                                warn!("<priority> has invalid format: {text:?}. Expected floating-point number.");
-                            }
-                        }
-                    }
-                    if let Some(loc) = loc {
-                        Some(UrlEntry::<'a> {
-                            location: loc,
-                            last_modified: lastmod,
-                            change_frequency: changefreq,
-                            priority,
-                        })
-                    } else {
-                        error!("Expected <loc>, but found none.");
-                        None
-                    }
-                })
+        })
+    }
+    /// Parse the `<sitemap>` entries, if the root is a `<sitemapindex>`.
+    fn sitemap_reports(
+        &'a self,
+    ) -> Result<
+        impl DoubleEndedIterator<Item = Report<SitemapEntry<'a>>>
+            + ExactSizeIterator
+            + Clone
+            + Debug
+            + 'a,
+        Error,
+    > {
+        let options = &self.options;
+        self.entries("sitemapindex").map(|entries| {
+            entries.map(move |(index, node)| {
+                let mut report = parse_sitemap_entry(index, node, options);
+                check_limits(
+                    &mut report,
+                    index,
+                    || node.position(),
+                    node.node.range().end as u64,
+                );
+                report
+            })
+        })
+    }
+    /// Get the element children of the root, if it's named `expected_tag`,
+    /// up to the [`ParseOptions::max_entries`].
+    fn entries(
+        &self,
+        expected_tag: &str,
+    ) -> Result<std::iter::Enumerate<std::vec::IntoIter<DomNode<'_>>>, Error> {
+        check_size(self.doc.input_text(), &self.options)?;
+        self.root_element(expected_tag).map(|node| {
+            DomNode::new(node, &self.lines)
+                .children()
+                .take(self.options.max_entries.unwrap_or(usize::MAX))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .enumerate()
+        })
+    }
+    fn root_element(&self, expected_tag: &str) -> Result<roxmltree::Node<'_, '_>, Error> {
+        let node = self.doc.root_element();
+        if node.tag_name().name() != expected_tag {
+            error!("Expected <{expected_tag}> but got {:?}", node);
+            Err(if expected_tag == "urlset" {
+                Error::UrlsetMissing
+            } else {
+                Error::SitemapIndexMissing
             })
-            .ok_or(Error::UrlsetMissing)
+        } else if !self.options.namespace_mode.accepts(self.namespace()) {
+            error!(
+                "Expected <{expected_tag}> in {NAMESPACE:?} but got {:?}",
+                self.namespace()
+            );
+            Err(Error::UnexpectedNamespace(
+                self.namespace().map(str::to_owned),
+            ))
+        } else {
+            Ok(node)
+        }
     }
 }
-fn node_text_expected_name<'a>(
-    node: &roxmltree::Node<'a, 'a>,
-    expected_tag: &str,
-) -> Option<&'a str> {
-    if node.tag_name().name() == expected_tag {
-        if let Some(text) = node.text() {
-            return Some(text);
+/// Returns [`Error::Rejected`] if any of the `reports` has a [`Severity::Fatal`] problem.
+fn check_fatal<T>(
+    options: &ParseOptions,
+    reports: impl Iterator<Item = Report<T>>,
+) -> Result<(), Error> {
+    if !options.can_fail() {
+        return Ok(());
+    }
+    for report in reports {
+        if let Some(diagnostic) = report
+            .diagnostics
+            .into_iter()
+            .find(|diagnostic| diagnostic.severity == Severity::Fatal)
+        {
+            return Err(Error::Rejected(diagnostic));
         }
     }
-    None
+    Ok(())
+}
+/// Returns `bytes` as UTF-8, or [`Error::UnsupportedEncoding`] if the byte order mark
+/// or XML declaration gives another encoding which changes the text.
+fn from_utf8(bytes: &[u8]) -> Result<&str, Error> {
+    if bytes.starts_with(b"\xff\xfe") || bytes.starts_with(b"<\0?\0") {
+        return Err(Error::UnsupportedEncoding("UTF-16LE".to_owned()));
+    }
+    if bytes.starts_with(b"\xfe\xff") || bytes.starts_with(b"\0<\0?") {
+        return Err(Error::UnsupportedEncoding("UTF-16BE".to_owned()));
+    }
+    let text = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    // The declaration is ASCII, so read it before decoding.
+    let end = text
+        .iter()
+        .position(|byte| *byte == b'>')
+        .map_or(0, |index| index + 1);
+    let declared = std::str::from_utf8(&text[..end])
+        .ok()
+        .and_then(declared_encoding)
+        .filter(|label| {
+            !label.eq_ignore_ascii_case("utf-8") && !label.eq_ignore_ascii_case("utf8")
+        });
+    match declared {
+        // ASCII is the same in all encodings which can be declared in ASCII.
+        Some(label) if !bytes.is_ascii() => Err(Error::UnsupportedEncoding(label.to_owned())),
+        _ => std::str::from_utf8(bytes).map_err(Error::Utf8),
+    }
+}
+/// The `encoding` of the XML declaration at the start of `text`.
+pub(crate) fn declared_encoding(text: &str) -> Option<&str> {
+    let declaration = text.strip_prefix("<?xml")?;
+    if !declaration.starts_with(|c: char| c.is_ascii_whitespace()) {
+        return None;
+    }
+    let declaration = &declaration[..declaration.find("?>")?];
+    let value = declaration
+        .split_once("encoding")?
+        .1
+        .trim_start()
+        .strip_prefix('=')?
+        .trim_start();
+    let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let value = &value[1..];
+    value.get(..value.find(quote)?)
+}
+/// Returns [`Error::TooLarge`] if `xml_document` exceeds the [`ParseOptions::max_size`].
+fn check_size(xml_document: &str, options: &ParseOptions) -> Result<(), Error> {
+    match options.max_size {
+        Some(max_size) if xml_document.len() as u64 > max_size => Err(Error::TooLarge(max_size)),
+        _ => Ok(()),
+    }
 }
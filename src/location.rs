@@ -0,0 +1,75 @@
+//! Parsing of `<loc>` as a [`Url`], with the `url` feature.
+//!
+//! The spec requires the locations to be absolute URLs, with the protocol,
+//! and to be [percent-encoded](https://www.rfc-editor.org/rfc/rfc3986#section-2.1).
+//! With this feature, entries which don't follow this get a [`crate::DiagnosticKind::InvalidLocation`]
+//! or a [`crate::DiagnosticKind::LocationNotPercentEncoded`] while parsing.
+
+use std::fmt::{self, Display};
+pub use url::Url;
+
+/// A `<loc>` which can't be used.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LocationError {
+    /// The location isn't a valid URL.
+    Invalid(url::ParseError),
+    /// The location is a relative URL, without the protocol.
+    Relative,
+    /// The scheme of the location isn't `http` or `https`.
+    ///
+    /// Contains the scheme.
+    UnsupportedScheme(String),
+}
+impl Display for LocationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(err) => write!(f, "invalid URL: {err}"),
+            Self::Relative => f.write_str("relative URL"),
+            Self::UnsupportedScheme(scheme) => {
+                write!(f, "unsupported scheme {scheme:?}, expected http or https")
+            }
+        }
+    }
+}
+impl std::error::Error for LocationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Invalid(err) => Some(err),
+            Self::Relative | Self::UnsupportedScheme(_) => None,
+        }
+    }
+}
+
+/// Parses `location` as an absolute `http` or `https` URL, trimming surrounding whitespace.
+///
+/// Characters which aren't percent-encoded are encoded in the returned [`Url`];
+/// see [`is_percent_encoded`] to check if `location` follows the spec.
+pub fn parse(location: &str) -> Result<Url, LocationError> {
+    let url = Url::parse(location.trim()).map_err(|err| match err {
+        url::ParseError::RelativeUrlWithoutBase => LocationError::Relative,
+        err => LocationError::Invalid(err),
+    })?;
+    match url.scheme() {
+        "http" | "https" => Ok(url),
+        scheme => Err(LocationError::UnsupportedScheme(scheme.to_owned())),
+    }
+}
+/// Check if `location`, without surrounding whitespace, is fully percent-encoded.
+///
+/// That is, it only contains the characters allowed in a
+/// [URI](https://www.rfc-editor.org/rfc/rfc3986#appendix-A), and every `%`
+/// is followed by two hexadecimal digits. Spaces and non-ASCII characters
+/// (e.g. in internationalized paths) must be encoded.
+pub fn is_percent_encoded(location: &str) -> bool {
+    let bytes = location.trim().as_bytes();
+    bytes.iter().enumerate().all(|(index, &byte)| match byte {
+        b'%' => bytes
+            .get(index + 1..index + 3)
+            .map_or(false, |hex| hex.iter().all(u8::is_ascii_hexdigit)),
+        b'-' | b'.' | b'_' | b'~' => true,
+        // Reserved characters.
+        b':' | b'/' | b'?' | b'#' | b'[' | b']' | b'@' => true,
+        b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' => true,
+        byte => byte.is_ascii_alphanumeric(),
+    })
+}
@@ -0,0 +1,237 @@
+//! The [news sitemap extension](https://developers.google.com/search/docs/crawling-indexing/sitemaps/news-sitemap).
+//!
+//! News are available in [`crate::UrlEntry::news`].
+
+use crate::parse::Element;
+use crate::{DiagnosticKind, W3cDatetime};
+
+/// The XML namespace of the news extension.
+pub const NAMESPACE: &str = "http://www.google.com/schemas/sitemap-news/0.9";
+/// The maximum number of `<url>` with a `<news:news>` in one sitemap.
+///
+/// Entries past this get a [`DiagnosticKind::TooManyNewsEntries`].
+pub const MAX_ENTRIES: usize = 1_000;
+
+/// The [ISO 639-1](https://en.wikipedia.org/wiki/List_of_ISO_639-1_codes) codes, sorted.
+pub(crate) const ISO_639_1: &[&str] = &[
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bh",
+    "bi", "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da",
+    "de", "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr",
+    "fy", "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz",
+    "ia", "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj",
+    "kk", "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln",
+    "lo", "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb",
+    "nd", "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi",
+    "pl", "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk",
+    "sl", "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti",
+    "tk", "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo",
+    "wa", "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
+
+/// Check if `language` is accepted by Google as the language of a [`Publication`].
+///
+/// These are two-letter [ISO 639-1](https://en.wikipedia.org/wiki/List_of_ISO_639-1_codes) codes,
+/// three-letter ISO 639-2/3 codes, and `zh-cn` & `zh-tw` for
+/// Simplified and Traditional Chinese.
+/// Three-letter codes are only checked to be ASCII letters.
+///
+/// The codes are case-insensitive, as in [BCP 47](https://www.rfc-editor.org/info/bcp47),
+/// so `EN` and `zh-CN` are valid too.
+pub fn is_valid_language(language: &str) -> bool {
+    let language = language.to_ascii_lowercase();
+    match language.len() {
+        2 => ISO_639_1.binary_search(&language.as_str()).is_ok(),
+        3 => language.bytes().all(|b| b.is_ascii_lowercase()),
+        _ => language == "zh-cn" || language == "zh-tw",
+    }
+}
+
+/// The publication a [`NewsInfo`] is from.
+///
+/// `<news:publication>`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Publication<'a> {
+    /// The name of the publication, as it appears on news.google.com.
+    ///
+    /// `<news:name>`
+    pub name: &'a str,
+    /// `<news:language>`
+    ///
+    /// Is kept even if it's not [valid](is_valid_language).
+    pub language: &'a str,
+}
+/// A news article on the page of a [`crate::UrlEntry`].
+///
+/// `<news:news>`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct NewsInfo<'a> {
+    /// `<news:publication>`
+    pub publication: Publication<'a>,
+    /// The date the article was first published.
+    ///
+    /// `<news:publication_date>`
+    ///
+    /// Format should be in [W3C Datetime](https://www.w3.org/TR/NOTE-datetime).
+    /// Is kept even if it isn't.
+    pub publication_date: &'a str,
+    /// `<news:title>`
+    pub title: &'a str,
+}
+impl<'a> NewsInfo<'a> {
+    /// Parse [`NewsInfo::publication_date`].
+    ///
+    /// See [`crate::UrlEntry::last_modified_datetime`].
+    pub fn publication_datetime(&self) -> Result<W3cDatetime, crate::DatetimeParseError> {
+        W3cDatetime::parse(self.publication_date)
+    }
+}
+
+/// An owned version of [`Publication`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OwnedPublication {
+    /// See [`Publication::name`].
+    pub name: String,
+    /// See [`Publication::language`].
+    pub language: String,
+}
+/// An owned version of [`NewsInfo`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OwnedNewsInfo {
+    /// See [`NewsInfo::publication`].
+    pub publication: OwnedPublication,
+    /// See [`NewsInfo::publication_date`].
+    pub publication_date: String,
+    /// See [`NewsInfo::title`].
+    pub title: String,
+}
+impl OwnedNewsInfo {
+    /// Borrow this as a [`NewsInfo`].
+    pub fn as_news(&self) -> NewsInfo<'_> {
+        NewsInfo {
+            publication: Publication {
+                name: &self.publication.name,
+                language: &self.publication.language,
+            },
+            publication_date: &self.publication_date,
+            title: &self.title,
+        }
+    }
+}
+impl<'a> From<NewsInfo<'a>> for OwnedNewsInfo {
+    fn from(news: NewsInfo<'a>) -> Self {
+        Self {
+            publication: OwnedPublication {
+                name: news.publication.name.to_owned(),
+                language: news.publication.language.to_owned(),
+            },
+            publication_date: news.publication_date.to_owned(),
+            title: news.title.to_owned(),
+        }
+    }
+}
+
+/// Check if the `<url>` `node` has a `<news:news>`.
+pub(crate) fn contains_news<'a, E: Element<'a>>(node: E) -> bool {
+    node.children()
+        .any(|child| child.namespace() == Some(NAMESPACE) && child.name() == "news")
+}
+
+/// Get the text of the `<news:{name}>` children of `node`, reporting duplicates.
+fn field<'a, E: Element<'a>>(
+    node: E,
+    name: &str,
+    diagnostic: &mut impl FnMut(E, DiagnosticKind),
+) -> Option<(E, &'a str)> {
+    let mut field = None;
+    for child in node.children() {
+        if child.namespace() != Some(NAMESPACE) || child.name() != name {
+            continue;
+        }
+        if let Some(text) = child.text() {
+            if field.is_some() {
+                diagnostic(
+                    child,
+                    DiagnosticKind::DuplicateExtensionField(format!("news:{name}")),
+                );
+            }
+            field = Some((child, text.trim()));
+        }
+    }
+    if field.is_none() {
+        diagnostic(
+            node,
+            DiagnosticKind::MissingExtensionField {
+                element: format!("news:{}", node.name()),
+                field: format!("news:{name}"),
+            },
+        );
+    }
+    field
+}
+
+/// Parses a `<news:news>`.
+/// Returns [`None`] if any of the required fields are missing.
+pub(crate) fn parse<'a, E: Element<'a>>(
+    node: E,
+    diagnostic: &mut impl FnMut(E, DiagnosticKind),
+) -> Option<NewsInfo<'a>> {
+    let mut publication = None;
+    for child in node.children() {
+        if child.namespace() != Some(NAMESPACE) || child.name() != "publication" {
+            continue;
+        }
+        if publication.is_some() {
+            diagnostic(
+                child,
+                DiagnosticKind::DuplicateExtensionField("news:publication".to_owned()),
+            );
+        }
+        publication = Some(child);
+    }
+    if publication.is_none() {
+        diagnostic(
+            node,
+            DiagnosticKind::MissingExtensionField {
+                element: "news:news".to_owned(),
+                field: "news:publication".to_owned(),
+            },
+        );
+    }
+    let publication = publication.and_then(|publication| {
+        let name = field(publication, "name", diagnostic);
+        let language = field(publication, "language", diagnostic);
+        if let Some((child, language)) = language {
+            if !is_valid_language(language) {
+                diagnostic(
+                    child,
+                    DiagnosticKind::InvalidExtensionField {
+                        field: "news:language".to_owned(),
+                        value: language.to_owned(),
+                    },
+                );
+            }
+        }
+        Some(Publication {
+            name: name?.1,
+            language: language?.1,
+        })
+    });
+    let publication_date = field(node, "publication_date", diagnostic);
+    if let Some((child, date)) = publication_date {
+        if W3cDatetime::parse(date).is_err() {
+            diagnostic(
+                child,
+                DiagnosticKind::InvalidExtensionField {
+                    field: "news:publication_date".to_owned(),
+                    value: date.to_owned(),
+                },
+            );
+        }
+    }
+    let title = field(node, "title", diagnostic);
+    Some(NewsInfo {
+        publication: publication?,
+        publication_date: publication_date?.1,
+        title: title?.1,
+    })
+}
@@ -0,0 +1,291 @@
+use crate::{DiagnosticKind, NamespaceMode, Severity};
+
+/// What to do with an entry which has multiple of a field.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DuplicatePolicy {
+    /// Fail the whole document.
+    Fail,
+    /// Skip the entry.
+    SkipEntry,
+    /// Use the first valid value.
+    KeepFirst,
+    /// Use the last valid value.
+    KeepLast,
+}
+impl DuplicatePolicy {
+    fn severity(self) -> Severity {
+        match self {
+            Self::Fail => Severity::Fatal,
+            Self::SkipEntry => Severity::Error,
+            Self::KeepFirst | Self::KeepLast => Severity::Warning,
+        }
+    }
+}
+/// What to do with a field with an invalid value.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InvalidPolicy {
+    /// Fail the whole document.
+    Fail,
+    /// Skip the entry.
+    SkipEntry,
+    /// Ignore the field.
+    Ignore,
+    /// Keep the text of the field.
+    ///
+    /// Only applies to `<loc>` and `<lastmod>`, which are stored as text.
+    /// For other fields, this is the same as [`InvalidPolicy::Ignore`].
+    Keep,
+}
+impl InvalidPolicy {
+    fn severity(self) -> Severity {
+        match self {
+            Self::Fail => Severity::Fatal,
+            Self::SkipEntry => Severity::Error,
+            Self::Ignore | Self::Keep => Severity::Warning,
+        }
+    }
+}
+/// What to do with a field outside its range, e.g. a `<priority>` outside `0.0..=1.0`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RangePolicy {
+    /// Fail the whole document.
+    Fail,
+    /// Skip the entry.
+    SkipEntry,
+    /// Ignore the field.
+    Ignore,
+    /// Use the closest value in the range.
+    Clamp,
+}
+impl RangePolicy {
+    fn severity(self) -> Severity {
+        match self {
+            Self::Fail => Severity::Fatal,
+            Self::SkipEntry => Severity::Error,
+            Self::Ignore | Self::Clamp => Severity::Warning,
+        }
+    }
+}
+
+/// How problems in entries are handled.
+///
+/// Each rule sets the [`Severity`] of the corresponding [`DiagnosticKind`].
+/// A [`Severity::Fatal`] diagnostic fails the whole document with [`crate::Error::Rejected`].
+///
+/// The default follows the spec leniently:
+/// a duplicate `<loc>` skips the entry, other duplicate fields keep the last valid value,
+/// an invalid `<lastmod>` is kept as text, and other invalid fields are ignored.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseOptions {
+    pub(crate) namespace_mode: NamespaceMode,
+    pub(crate) duplicate_location: DuplicatePolicy,
+    pub(crate) duplicate_last_modified: DuplicatePolicy,
+    pub(crate) duplicate_change_frequency: DuplicatePolicy,
+    pub(crate) duplicate_priority: DuplicatePolicy,
+    pub(crate) invalid_location: InvalidPolicy,
+    pub(crate) invalid_last_modified: InvalidPolicy,
+    pub(crate) invalid_change_frequency: InvalidPolicy,
+    pub(crate) invalid_priority: InvalidPolicy,
+    pub(crate) priority_out_of_range: RangePolicy,
+    pub(crate) extension_out_of_range: RangePolicy,
+    pub(crate) blank_line: InvalidPolicy,
+    pub(crate) max_entries: Option<usize>,
+    pub(crate) max_size: Option<u64>,
+}
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            namespace_mode: NamespaceMode::default(),
+            duplicate_location: DuplicatePolicy::SkipEntry,
+            duplicate_last_modified: DuplicatePolicy::KeepLast,
+            duplicate_change_frequency: DuplicatePolicy::KeepLast,
+            duplicate_priority: DuplicatePolicy::KeepLast,
+            invalid_location: InvalidPolicy::Keep,
+            invalid_last_modified: InvalidPolicy::Keep,
+            invalid_change_frequency: InvalidPolicy::Ignore,
+            invalid_priority: InvalidPolicy::Ignore,
+            priority_out_of_range: RangePolicy::Ignore,
+            extension_out_of_range: RangePolicy::Ignore,
+            blank_line: InvalidPolicy::Ignore,
+            max_entries: None,
+            max_size: None,
+        }
+    }
+}
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Fail the document on any problem which has a rule, and require the sitemap namespace.
+    ///
+    /// Suitable for validators. The other problems, e.g. in the image extension or
+    /// past the limits of the protocol, are still [`Severity::Warning`].
+    pub fn strict() -> Self {
+        Self {
+            namespace_mode: NamespaceMode::Strict,
+            duplicate_location: DuplicatePolicy::Fail,
+            duplicate_last_modified: DuplicatePolicy::Fail,
+            duplicate_change_frequency: DuplicatePolicy::Fail,
+            duplicate_priority: DuplicatePolicy::Fail,
+            invalid_location: InvalidPolicy::Fail,
+            invalid_last_modified: InvalidPolicy::Fail,
+            invalid_change_frequency: InvalidPolicy::Fail,
+            invalid_priority: InvalidPolicy::Fail,
+            priority_out_of_range: RangePolicy::Fail,
+            extension_out_of_range: RangePolicy::Fail,
+            blank_line: InvalidPolicy::Fail,
+            max_entries: None,
+            max_size: None,
+        }
+    }
+    /// Recover as many entries as possible.
+    ///
+    /// Suitable for crawlers.
+    /// Duplicate `<loc>` use the first one, and fields out of range are clamped.
+    pub fn lenient() -> Self {
+        Self {
+            duplicate_location: DuplicatePolicy::KeepFirst,
+            priority_out_of_range: RangePolicy::Clamp,
+            extension_out_of_range: RangePolicy::Clamp,
+            ..Self::default()
+        }
+    }
+    /// Set how namespaces are checked.
+    pub fn namespace_mode(mut self, namespace_mode: NamespaceMode) -> Self {
+        self.namespace_mode = namespace_mode;
+        self
+    }
+    /// Set what to do with multiple `<loc>` in an entry.
+    pub fn duplicate_location(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_location = policy;
+        self
+    }
+    /// Set what to do with multiple `<lastmod>` in an entry.
+    pub fn duplicate_last_modified(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_last_modified = policy;
+        self
+    }
+    /// Set what to do with multiple `<changefreq>` in an entry.
+    pub fn duplicate_change_frequency(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_change_frequency = policy;
+        self
+    }
+    /// Set what to do with multiple `<priority>` in an entry.
+    pub fn duplicate_priority(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_priority = policy;
+        self
+    }
+    /// Set what to do with a `<loc>` which isn't an absolute `http` or `https` URL.
+    ///
+    /// Only checked with the `url` feature. An entry needs a location, so
+    /// [`InvalidPolicy::Ignore`] keeps it, like [`InvalidPolicy::Keep`].
+    pub fn invalid_location(mut self, policy: InvalidPolicy) -> Self {
+        self.invalid_location = policy;
+        self
+    }
+    /// Set what to do with a `<lastmod>` not in the
+    /// [W3C Datetime](https://www.w3.org/TR/NOTE-datetime) format.
+    pub fn invalid_last_modified(mut self, policy: InvalidPolicy) -> Self {
+        self.invalid_last_modified = policy;
+        self
+    }
+    /// Set what to do with a `<changefreq>` which isn't a [`crate::Frequency`].
+    pub fn invalid_change_frequency(mut self, policy: InvalidPolicy) -> Self {
+        self.invalid_change_frequency = policy;
+        self
+    }
+    /// Set what to do with a `<priority>` which isn't a number.
+    pub fn invalid_priority(mut self, policy: InvalidPolicy) -> Self {
+        self.invalid_priority = policy;
+        self
+    }
+    /// Set what to do with a `<priority>` outside `0.0..=1.0`.
+    pub fn priority_out_of_range(mut self, policy: RangePolicy) -> Self {
+        self.priority_out_of_range = policy;
+        self
+    }
+    /// Set what to do with a field of an extension out of its range,
+    /// i.e. a `<video:duration>` outside `1..=28800` or a `<video:rating>` outside `0.0..=5.0`.
+    pub fn extension_out_of_range(mut self, policy: RangePolicy) -> Self {
+        self.extension_out_of_range = policy;
+        self
+    }
+    /// Set what to do with a blank line in a [`crate::TextSitemap`].
+    ///
+    /// The line has no entry either way; [`InvalidPolicy::Ignore`] and [`InvalidPolicy::Keep`]
+    /// only give a [`Severity::Warning`].
+    pub fn blank_line(mut self, policy: InvalidPolicy) -> Self {
+        self.blank_line = policy;
+        self
+    }
+
+    /// Stop after `max_entries` entries; the rest of the document is ignored.
+    ///
+    /// Unlimited by default. The protocol allows [`crate::writer::MAX_ENTRIES`],
+    /// and more give [`DiagnosticKind::TooManyEntries`].
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+    /// Stop after `max_size` bytes, with [`crate::Error::TooLarge`].
+    ///
+    /// Unlimited by default. The protocol allows [`crate::writer::MAX_SIZE`],
+    /// and entries past it give [`DiagnosticKind::FileTooLarge`].
+    /// Set this to protect against hostile input, using
+    /// [`crate::Document::parse_with_options`] or [`crate::StreamParser::with_options`]
+    /// so the input is checked before being parsed.
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Get the [`Severity`] of `kind` with these options.
+    pub fn severity(&self, kind: &DiagnosticKind) -> Severity {
+        match kind {
+            DiagnosticKind::MissingLocation => Severity::Error,
+            DiagnosticKind::BlankLine => self.blank_line.severity(),
+            DiagnosticKind::DuplicateLocation => self.duplicate_location.severity(),
+            DiagnosticKind::DuplicateLastModified => self.duplicate_last_modified.severity(),
+            DiagnosticKind::DuplicateChangeFrequency => self.duplicate_change_frequency.severity(),
+            DiagnosticKind::DuplicatePriority => self.duplicate_priority.severity(),
+            DiagnosticKind::InvalidLocation(_) => self.invalid_location.severity(),
+            DiagnosticKind::InvalidLastModified(_) => self.invalid_last_modified.severity(),
+            DiagnosticKind::InvalidChangeFrequency(_) => self.invalid_change_frequency.severity(),
+            DiagnosticKind::InvalidPriority(_) => self.invalid_priority.severity(),
+            DiagnosticKind::PriorityOutOfRange(_) => self.priority_out_of_range.severity(),
+            DiagnosticKind::ExtensionFieldOutOfRange { .. } => {
+                self.extension_out_of_range.severity()
+            }
+            DiagnosticKind::DuplicateExtensionField(_)
+            | DiagnosticKind::MissingExtensionField { .. }
+            | DiagnosticKind::MissingOneOfExtensionFields { .. }
+            | DiagnosticKind::InvalidExtensionField { .. }
+            | DiagnosticKind::TooManyNewsEntries
+            | DiagnosticKind::TooManyEntries
+            | DiagnosticKind::FileTooLarge
+            | DiagnosticKind::LocationTooLong(_)
+            | DiagnosticKind::LocationNotPercentEncoded(_)
+            | DiagnosticKind::UnexpectedNamespace { .. } => Severity::Warning,
+        }
+    }
+    /// Check if any rule can fail the document.
+    pub(crate) fn can_fail(&self) -> bool {
+        [
+            self.duplicate_location,
+            self.duplicate_last_modified,
+            self.duplicate_change_frequency,
+            self.duplicate_priority,
+        ]
+        .contains(&DuplicatePolicy::Fail)
+            || [
+                self.invalid_location,
+                self.invalid_last_modified,
+                self.invalid_change_frequency,
+                self.invalid_priority,
+                self.blank_line,
+            ]
+            .contains(&InvalidPolicy::Fail)
+            || [self.priority_out_of_range, self.extension_out_of_range]
+                .contains(&RangePolicy::Fail)
+    }
+}
@@ -0,0 +1,248 @@
+//! Entries and documents which own their data.
+//!
+//! These can outlive the input and be sent between threads.
+
+use crate::{
+    DatetimeParseError, Diagnostic, Document, DocumentKind, Error, Frequency, OwnedAlternate,
+    OwnedExtensionElement, OwnedImage, OwnedNewsInfo, OwnedVideo, ParseOptions, Report,
+    SitemapEntry, UrlEntry, W3cDatetime,
+};
+
+/// An owned version of [`UrlEntry`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct OwnedUrlEntry {
+    /// See [`UrlEntry::location`].
+    pub location: String,
+    /// See [`UrlEntry::last_modified`].
+    pub last_modified: Option<String>,
+    /// See [`UrlEntry::change_frequency`].
+    pub change_frequency: Option<Frequency>,
+    /// See [`UrlEntry::priority`].
+    pub priority: Option<f32>,
+    /// See [`UrlEntry::images`].
+    pub images: Vec<OwnedImage>,
+    /// See [`UrlEntry::videos`].
+    pub videos: Vec<OwnedVideo>,
+    /// See [`UrlEntry::news`].
+    pub news: Option<OwnedNewsInfo>,
+    /// See [`UrlEntry::alternates`].
+    pub alternates: Vec<OwnedAlternate>,
+    /// See [`UrlEntry::extensions`].
+    pub extensions: Vec<OwnedExtensionElement>,
+}
+impl OwnedUrlEntry {
+    /// Convert this to a [`UrlEntry`] borrowing from `self`,
+    /// e.g. to write it using [`crate::SitemapWriter`].
+    pub fn as_entry(&self) -> UrlEntry<'_> {
+        UrlEntry {
+            location: &self.location,
+            last_modified: self.last_modified.as_deref(),
+            change_frequency: self.change_frequency,
+            priority: self.priority,
+            images: self.images.iter().map(OwnedImage::as_image).collect(),
+            videos: self.videos.iter().map(OwnedVideo::as_video).collect(),
+            news: self.news.as_ref().map(OwnedNewsInfo::as_news),
+            alternates: self
+                .alternates
+                .iter()
+                .map(OwnedAlternate::as_alternate)
+                .collect(),
+            extensions: self
+                .extensions
+                .iter()
+                .map(OwnedExtensionElement::as_element)
+                .collect(),
+        }
+    }
+    /// Parses [`OwnedUrlEntry::last_modified`].
+    ///
+    /// Returns [`None`] if there's no `<lastmod>`.
+    pub fn last_modified_datetime(&self) -> Option<Result<W3cDatetime, DatetimeParseError>> {
+        self.last_modified.as_deref().map(W3cDatetime::parse)
+    }
+    /// Parses [`OwnedUrlEntry::location`] using [`crate::location::parse`].
+    #[cfg(feature = "url")]
+    pub fn location_url(&self) -> Result<crate::location::Url, crate::location::LocationError> {
+        crate::location::parse(&self.location)
+    }
+}
+impl<'a> From<UrlEntry<'a>> for OwnedUrlEntry {
+    fn from(entry: UrlEntry<'a>) -> Self {
+        Self {
+            location: entry.location.to_owned(),
+            last_modified: entry.last_modified.map(str::to_owned),
+            change_frequency: entry.change_frequency,
+            priority: entry.priority,
+            images: entry.images.into_iter().map(OwnedImage::from).collect(),
+            videos: entry.videos.into_iter().map(OwnedVideo::from).collect(),
+            news: entry.news.map(OwnedNewsInfo::from),
+            alternates: entry
+                .alternates
+                .into_iter()
+                .map(OwnedAlternate::from)
+                .collect(),
+            extensions: entry
+                .extensions
+                .into_iter()
+                .map(OwnedExtensionElement::from)
+                .collect(),
+        }
+    }
+}
+/// An owned version of [`SitemapEntry`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OwnedSitemapEntry {
+    /// See [`SitemapEntry::location`].
+    pub location: String,
+    /// See [`SitemapEntry::last_modified`].
+    pub last_modified: Option<String>,
+}
+impl OwnedSitemapEntry {
+    /// Borrow this as a [`SitemapEntry`], e.g. to write it using [`crate::SitemapIndexWriter`].
+    pub fn as_entry(&self) -> SitemapEntry<'_> {
+        SitemapEntry {
+            location: &self.location,
+            last_modified: self.last_modified.as_deref(),
+        }
+    }
+    /// Parses [`OwnedSitemapEntry::last_modified`].
+    ///
+    /// Returns [`None`] if there's no `<lastmod>`.
+    pub fn last_modified_datetime(&self) -> Option<Result<W3cDatetime, DatetimeParseError>> {
+        self.last_modified.as_deref().map(W3cDatetime::parse)
+    }
+    /// Parses [`OwnedSitemapEntry::location`] using [`crate::location::parse`].
+    #[cfg(feature = "url")]
+    pub fn location_url(&self) -> Result<crate::location::Url, crate::location::LocationError> {
+        crate::location::parse(&self.location)
+    }
+}
+impl<'a> From<SitemapEntry<'a>> for OwnedSitemapEntry {
+    fn from(entry: SitemapEntry<'a>) -> Self {
+        Self {
+            location: entry.location.to_owned(),
+            last_modified: entry.last_modified.map(str::to_owned),
+        }
+    }
+}
+
+/// A parsed sitemap which owns it's entries.
+///
+/// Unlike [`Document`], this doesn't borrow the input,
+/// so it can be stored and sent between threads.
+/// All entries are parsed up front.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OwnedDocument {
+    kind: DocumentKind,
+    entries: Vec<OwnedUrlEntry>,
+    sitemaps: Vec<OwnedSitemapEntry>,
+    diagnostics: Vec<Diagnostic>,
+    #[cfg(feature = "encoding_rs")]
+    encoding_mismatches: Vec<crate::encoding::Mismatch>,
+}
+impl OwnedDocument {
+    /// Parses `xml_document`, which can be dropped afterwards.
+    ///
+    /// Returns [`Error::UrlsetMissing`] if the root is neither a `<urlset>` nor a `<sitemapindex>`.
+    pub fn parse(xml_document: &str) -> Result<Self, Error> {
+        Self::parse_with_options(xml_document, ParseOptions::default())
+    }
+    /// Parses `xml_document` with `options`.
+    ///
+    /// See [`OwnedDocument::parse`] and [`Document::options`].
+    pub fn parse_with_options(xml_document: &str, options: ParseOptions) -> Result<Self, Error> {
+        let doc = Document::parse_with_options(xml_document, options)?;
+        if !doc.options.namespace_mode.accepts(doc.namespace()) {
+            return Err(Error::UnexpectedNamespace(
+                doc.namespace().map(str::to_owned),
+            ));
+        }
+        let mut me = Self {
+            kind: doc.kind().ok_or(Error::UrlsetMissing)?,
+            entries: Vec::new(),
+            sitemaps: Vec::new(),
+            diagnostics: Vec::new(),
+            #[cfg(feature = "encoding_rs")]
+            encoding_mismatches: Vec::new(),
+        };
+        match me.kind {
+            DocumentKind::Urlset => {
+                for report in doc.iterate_with_diagnostics()? {
+                    if let Some(entry) = me.add(report) {
+                        me.entries.push(entry.into());
+                    }
+                }
+            }
+            DocumentKind::SitemapIndex => {
+                for report in doc.iterate_sitemaps_with_diagnostics()? {
+                    if let Some(entry) = me.add(report) {
+                        me.sitemaps.push(entry.into());
+                    }
+                }
+            }
+        }
+        Ok(me)
+    }
+    /// Parses `bytes`, which must be UTF-8, like [`Document::from_bytes`].
+    ///
+    /// With the `encoding_rs` feature, other encodings are transcoded using `encoding::decode`,
+    /// and disagreements with the declared encoding are kept in `OwnedDocument::encoding_mismatches`.
+    ///
+    /// See [`OwnedDocument::parse`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        #[cfg(feature = "encoding_rs")]
+        {
+            let decoded = crate::encoding::decode(bytes);
+            let mut me = Self::parse(decoded.text())?;
+            me.encoding_mismatches = decoded.mismatches().to_vec();
+            Ok(me)
+        }
+        #[cfg(not(feature = "encoding_rs"))]
+        {
+            crate::from_utf8(bytes).and_then(Self::parse)
+        }
+    }
+    /// Returns the kind of this document, based on the root element.
+    pub fn kind(&self) -> DocumentKind {
+        self.kind
+    }
+    /// The valid entries of the `<urlset>`.
+    ///
+    /// Empty if [`OwnedDocument::kind`] is [`DocumentKind::SitemapIndex`].
+    pub fn entries(&self) -> &[OwnedUrlEntry] {
+        &self.entries
+    }
+    /// Take the [`OwnedDocument::entries`].
+    pub fn into_entries(self) -> Vec<OwnedUrlEntry> {
+        self.entries
+    }
+    /// The valid entries of the `<sitemapindex>`.
+    ///
+    /// Empty if [`OwnedDocument::kind`] is [`DocumentKind::Urlset`].
+    pub fn sitemaps(&self) -> &[OwnedSitemapEntry] {
+        &self.sitemaps
+    }
+    /// Take the [`OwnedDocument::sitemaps`].
+    pub fn into_sitemaps(self) -> Vec<OwnedSitemapEntry> {
+        self.sitemaps
+    }
+    /// All the problems found in the entries of this document.
+    ///
+    /// These are not logged.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+    /// The disagreements between the declared and the actual encoding
+    /// of the input of [`OwnedDocument::from_bytes`].
+    ///
+    /// These are not logged.
+    #[cfg(feature = "encoding_rs")]
+    pub fn encoding_mismatches(&self) -> &[crate::encoding::Mismatch] {
+        &self.encoding_mismatches
+    }
+
+    fn add<T>(&mut self, report: Report<T>) -> Option<T> {
+        self.diagnostics.extend(report.diagnostics);
+        report.entry
+    }
+}
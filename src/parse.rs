@@ -0,0 +1,466 @@
+//! Parsing of entries, shared by [`crate::Document`] and [`crate::StreamParser`].
+
+use crate::{extension, image, news, schema, video, writer, xhtml};
+use crate::{
+    Diagnostic, DiagnosticKind, DuplicatePolicy, ExtensionAttribute, InvalidPolicy, NamespaceMode,
+    ParseOptions, RangePolicy, Report, Severity, SitemapEntry, TextPos, UrlEntry, W3cDatetime,
+};
+use std::fmt::{self, Debug};
+
+/// An XML element, either from a [`roxmltree::Document`]
+/// or collected by [`crate::StreamParser`].
+pub(crate) trait Element<'a>: Copy {
+    type Children: Iterator<Item = Self>;
+
+    /// The local name of the element.
+    fn name(&self) -> &'a str;
+    /// The namespace URI of the element.
+    fn namespace(&self) -> Option<&'a str>;
+    /// The value of the attribute `name`, without a namespace.
+    fn attribute(&self, name: &str) -> Option<&'a str>;
+    /// All attributes of the element, except namespace declarations.
+    fn attributes(&self) -> Vec<ExtensionAttribute<'a>>;
+    /// The text content of the element.
+    fn text(&self) -> Option<&'a str>;
+    /// The source between the start and end tags.
+    fn inner_xml(&self) -> &'a str;
+    /// The child elements of this element.
+    fn children(&self) -> Self::Children;
+    /// The position of the start of this element.
+    fn position(&self) -> TextPos;
+}
+/// The byte offsets of the line starts of a text, to find the [`TextPos`] of an offset
+/// without scanning the text from its start like [`roxmltree::Document::text_pos_at`].
+#[derive(Debug, Clone)]
+pub(crate) struct LineIndex(Vec<usize>);
+impl LineIndex {
+    pub(crate) fn new(text: &str) -> Self {
+        let starts = text.match_indices('\n').map(|(index, _)| index + 1);
+        Self(std::iter::once(0).chain(starts).collect())
+    }
+    /// The position of the byte `offset` of `text`, the same as
+    /// [`roxmltree::Document::text_pos_at`].
+    pub(crate) fn position(&self, text: &str, offset: usize) -> TextPos {
+        let offset = offset.min(text.len());
+        let row = self.0.partition_point(|start| *start <= offset);
+        let column = text[self.0[row - 1]..offset].chars().count() + 1;
+        TextPos::new(row as u32, column as u32)
+    }
+}
+
+/// A [`roxmltree::Node`] with the [`LineIndex`] of its document.
+#[derive(Clone, Copy)]
+pub(crate) struct DomNode<'a> {
+    pub(crate) node: roxmltree::Node<'a, 'a>,
+    lines: &'a LineIndex,
+}
+impl<'a> DomNode<'a> {
+    pub(crate) fn new(node: roxmltree::Node<'a, 'a>, lines: &'a LineIndex) -> Self {
+        Self { node, lines }
+    }
+}
+impl Debug for DomNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.node.fmt(f)
+    }
+}
+impl<'a> Element<'a> for DomNode<'a> {
+    type Children = DomChildren<'a>;
+
+    fn name(&self) -> &'a str {
+        self.node.tag_name().name()
+    }
+    fn namespace(&self) -> Option<&'a str> {
+        // roxmltree reports `xmlns=""` as the empty namespace.
+        self.node
+            .tag_name()
+            .namespace()
+            .filter(|namespace| !namespace.is_empty())
+    }
+    fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.node.attribute(name)
+    }
+    fn attributes(&self) -> Vec<ExtensionAttribute<'a>> {
+        self.node
+            .attributes()
+            .iter()
+            .map(|attribute| ExtensionAttribute {
+                namespace: attribute.namespace(),
+                name: attribute.name(),
+                value: attribute.value(),
+            })
+            .collect()
+    }
+    fn text(&self) -> Option<&'a str> {
+        self.node.text()
+    }
+    fn inner_xml(&self) -> &'a str {
+        let source = &self.node.document().input_text()[self.node.range()];
+        // Find the end of the start tag, skipping `>` in attribute values.
+        let mut quote = None;
+        let start = source.bytes().position(|byte| match quote {
+            Some(q) if byte == q => {
+                quote = None;
+                false
+            }
+            Some(_) => false,
+            None if byte == b'"' || byte == b'\'' => {
+                quote = Some(byte);
+                false
+            }
+            None => byte == b'>',
+        });
+        match start {
+            Some(start) if source.as_bytes()[start - 1] != b'/' => {
+                let end = source.rfind("</").unwrap_or(start + 1);
+                &source[start + 1..end]
+            }
+            _ => "",
+        }
+    }
+    fn children(&self) -> Self::Children {
+        DomChildren {
+            children: self.node.children(),
+            lines: self.lines,
+        }
+    }
+    fn position(&self) -> TextPos {
+        let text = self.node.document().input_text();
+        self.lines.position(text, self.node.range().start)
+    }
+}
+/// The child elements of a [`DomNode`].
+#[derive(Clone)]
+pub(crate) struct DomChildren<'a> {
+    children: roxmltree::Children<'a, 'a>,
+    lines: &'a LineIndex,
+}
+impl<'a> Iterator for DomChildren<'a> {
+    type Item = DomNode<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let lines = self.lines;
+        self.children
+            .find(|child| child.is_element())
+            .map(|node| DomNode { node, lines })
+    }
+}
+
+/// Check if `node` is a field of the sitemap protocol, reporting it if it's in the wrong namespace.
+fn is_field<'a, E: Element<'a>>(
+    node: E,
+    mode: NamespaceMode,
+    diagnostic: &mut impl FnMut(E, DiagnosticKind),
+) -> bool {
+    if !matches!(node.name(), "loc" | "lastmod" | "changefreq" | "priority") {
+        return false;
+    }
+    check_namespace(node, mode, diagnostic)
+}
+/// Report `node` if it's in a namespace not accepted by `mode`.
+fn check_namespace<'a, E: Element<'a>>(
+    node: E,
+    mode: NamespaceMode,
+    diagnostic: &mut impl FnMut(E, DiagnosticKind),
+) -> bool {
+    let accepted = mode.accepts(node.namespace());
+    if !accepted {
+        diagnostic(
+            node,
+            DiagnosticKind::UnexpectedNamespace {
+                element: node.name().to_owned(),
+                namespace: node.namespace().map(str::to_owned),
+            },
+        );
+    }
+    accepted
+}
+/// Whether a field with `policy` replaces a previous value.
+fn replaces(policy: DuplicatePolicy) -> bool {
+    policy != DuplicatePolicy::KeepFirst
+}
+/// Report the `<loc>` `node` with the [`location_problems`] of `text`.
+fn check_location<'a, E: Element<'a>>(
+    node: E,
+    text: &str,
+    diagnostic: &mut impl FnMut(E, DiagnosticKind),
+) {
+    for kind in location_problems(text) {
+        diagnostic(node, kind);
+    }
+}
+/// The problems with the location `text`: it's too long,
+/// or, with the `url` feature, isn't a valid URL.
+pub(crate) fn location_problems(text: &str) -> Vec<DiagnosticKind> {
+    let mut problems = Vec::new();
+    let length = text.trim().chars().count();
+    if length > schema::MAX_LOCATION_LENGTH {
+        problems.push(DiagnosticKind::LocationTooLong(length));
+    }
+    #[cfg(feature = "url")]
+    if crate::location::parse(text).is_err() {
+        problems.push(DiagnosticKind::InvalidLocation(text.to_owned()));
+    } else if !crate::location::is_percent_encoded(text) {
+        problems.push(DiagnosticKind::LocationNotPercentEncoded(text.to_owned()));
+    }
+    problems
+}
+/// Parses the text of a `<lastmod>`, returning it if it should be kept.
+fn parse_last_modified<'a, E: Element<'a>>(
+    node: E,
+    text: &'a str,
+    options: &ParseOptions,
+    diagnostic: &mut impl FnMut(E, DiagnosticKind),
+) -> Option<&'a str> {
+    if W3cDatetime::parse(text).is_ok() {
+        Some(text)
+    } else {
+        diagnostic(node, DiagnosticKind::InvalidLastModified(text.to_owned()));
+        Some(text).filter(|_| options.invalid_last_modified == InvalidPolicy::Keep)
+    }
+}
+pub(crate) fn parse_url_entry<'a, E: Element<'a>>(
+    index: usize,
+    node: E,
+    options: &ParseOptions,
+) -> Report<UrlEntry<'a>> {
+    let mut diagnostics = Vec::new();
+    let mut diagnostic = |node: E, kind| {
+        diagnostics.push(Diagnostic {
+            entry: index,
+            position: node.position(),
+            severity: options.severity(&kind),
+            kind,
+        })
+    };
+    let mut loc = None;
+    let mut lastmod = None;
+    let mut changefreq = None;
+    let mut priority = None;
+    let mut images = Vec::new();
+    let mut videos = Vec::new();
+    let mut news = None;
+    let mut alternates = Vec::new();
+    let mut extensions = Vec::new();
+    check_namespace(node, options.namespace_mode, &mut diagnostic);
+    for child in node.children() {
+        if is_field(child, options.namespace_mode, &mut diagnostic) {
+            let text = if let Some(text) = child.text() {
+                text
+            } else {
+                continue;
+            };
+            match child.name() {
+                "loc" => {
+                    if loc.is_some() {
+                        diagnostic(child, DiagnosticKind::DuplicateLocation);
+                    }
+                    check_location(child, text, &mut diagnostic);
+                    if loc.is_none() || replaces(options.duplicate_location) {
+                        loc = Some(text);
+                    }
+                }
+                "lastmod" => {
+                    if lastmod.is_some() {
+                        diagnostic(child, DiagnosticKind::DuplicateLastModified);
+                    }
+                    let value = parse_last_modified(child, text, options, &mut diagnostic);
+                    if lastmod.is_none() || replaces(options.duplicate_last_modified) {
+                        lastmod = value.or(lastmod);
+                    }
+                }
+                "changefreq" => {
+                    if changefreq.is_some() {
+                        diagnostic(child, DiagnosticKind::DuplicateChangeFrequency);
+                    }
+                    let value = text.parse().ok();
+                    if value.is_none() {
+                        diagnostic(
+                            child,
+                            DiagnosticKind::InvalidChangeFrequency(text.to_owned()),
+                        );
+                    }
+                    if changefreq.is_none() || replaces(options.duplicate_change_frequency) {
+                        changefreq = value.or(changefreq);
+                    }
+                }
+                _ => {
+                    if priority.is_some() {
+                        diagnostic(child, DiagnosticKind::DuplicatePriority);
+                    }
+                    let value = match text.parse::<f32>() {
+                        Ok(num) if (0.0..=1.0).contains(&num) => Some(num),
+                        Ok(num) => {
+                            diagnostic(child, DiagnosticKind::PriorityOutOfRange(text.to_owned()));
+                            Some(num.clamp(0.0, 1.0)).filter(|_| {
+                                options.priority_out_of_range == RangePolicy::Clamp && !num.is_nan()
+                            })
+                        }
+                        Err(_) => {
+                            diagnostic(child, DiagnosticKind::InvalidPriority(text.to_owned()));
+                            None
+                        }
+                    };
+                    if priority.is_none() || replaces(options.duplicate_priority) {
+                        priority = value.or(priority);
+                    }
+                }
+            }
+        } else if child.namespace() == Some(image::NAMESPACE) && child.name() == "image" {
+            images.extend(image::parse(child, &mut diagnostic));
+        } else if child.namespace() == Some(video::NAMESPACE) && child.name() == "video" {
+            videos.extend(video::parse(
+                child,
+                options.extension_out_of_range,
+                &mut diagnostic,
+            ));
+        } else if child.namespace() == Some(news::NAMESPACE) && child.name() == "news" {
+            if news.is_some() {
+                diagnostic(
+                    child,
+                    DiagnosticKind::DuplicateExtensionField("news:news".to_owned()),
+                );
+            }
+            news = news::parse(child, &mut diagnostic).or(news);
+        } else if child.namespace() == Some(xhtml::NAMESPACE) && child.name() == "link" {
+            match xhtml::parse(child, &mut diagnostic) {
+                Some(alternate) => alternates.push(alternate),
+                None => extensions.push(extension::parse(child)),
+            }
+        } else {
+            extensions.push(extension::parse(child));
+        }
+    }
+    if loc.is_none() {
+        diagnostic(node, DiagnosticKind::MissingLocation);
+    }
+    let skipped = diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity >= Severity::Error);
+    let entry = loc.filter(|_| !skipped).map(|loc| UrlEntry::<'a> {
+        location: loc,
+        last_modified: lastmod,
+        change_frequency: changefreq,
+        priority,
+        images,
+        videos,
+        news,
+        alternates,
+        extensions,
+    });
+    Report {
+        index,
+        entry,
+        diagnostics,
+    }
+}
+/// Adds [`DiagnosticKind::TooManyNewsEntries`] to `report` if the `<url>` `node`
+/// has a `<news:news>` and is past [`news::MAX_ENTRIES`].
+pub(crate) fn check_news_limit<'a, E: Element<'a>>(
+    report: &mut Report<UrlEntry<'a>>,
+    node: E,
+    past_limit: bool,
+) {
+    if past_limit && news::contains_news(node) {
+        report.diagnostics.push(Diagnostic {
+            entry: report.index,
+            position: node.position(),
+            severity: Severity::Warning,
+            kind: DiagnosticKind::TooManyNewsEntries,
+        });
+    }
+}
+/// Adds [`DiagnosticKind::TooManyEntries`] and [`DiagnosticKind::FileTooLarge`] to `report`
+/// if the entry, which is preceded by `count` entries and ends at byte `end`,
+/// is past the limits of the protocol.
+///
+/// The `position` of the entry is only computed if a diagnostic is added.
+pub(crate) fn check_limits<T>(
+    report: &mut Report<T>,
+    count: usize,
+    position: impl FnOnce() -> TextPos,
+    end: u64,
+) {
+    let mut kinds = [
+        Some(DiagnosticKind::TooManyEntries).filter(|_| count >= writer::MAX_ENTRIES),
+        Some(DiagnosticKind::FileTooLarge).filter(|_| end > writer::MAX_SIZE),
+    ]
+    .into_iter()
+    .flatten()
+    .peekable();
+    if kinds.peek().is_none() {
+        return;
+    }
+    let position = position();
+    for kind in kinds {
+        report.diagnostics.push(Diagnostic {
+            entry: report.index,
+            position,
+            severity: Severity::Warning,
+            kind,
+        });
+    }
+}
+pub(crate) fn parse_sitemap_entry<'a, E: Element<'a>>(
+    index: usize,
+    node: E,
+    options: &ParseOptions,
+) -> Report<SitemapEntry<'a>> {
+    let mut diagnostics = Vec::new();
+    let mut diagnostic = |node: E, kind| {
+        diagnostics.push(Diagnostic {
+            entry: index,
+            position: node.position(),
+            severity: options.severity(&kind),
+            kind,
+        })
+    };
+    let mut loc = None;
+    let mut lastmod = None;
+    check_namespace(node, options.namespace_mode, &mut diagnostic);
+    for child in node.children() {
+        if !is_field(child, options.namespace_mode, &mut diagnostic) {
+            continue;
+        }
+        let text = if let Some(text) = child.text() {
+            text
+        } else {
+            continue;
+        };
+        match child.name() {
+            "loc" => {
+                if loc.is_some() {
+                    diagnostic(child, DiagnosticKind::DuplicateLocation);
+                }
+                check_location(child, text, &mut diagnostic);
+                if loc.is_none() || replaces(options.duplicate_location) {
+                    loc = Some(text);
+                }
+            }
+            "lastmod" => {
+                if lastmod.is_some() {
+                    diagnostic(child, DiagnosticKind::DuplicateLastModified);
+                }
+                let value = parse_last_modified(child, text, options, &mut diagnostic);
+                if lastmod.is_none() || replaces(options.duplicate_last_modified) {
+                    lastmod = value.or(lastmod);
+                }
+            }
+            _ => {}
+        }
+    }
+    if loc.is_none() {
+        diagnostic(node, DiagnosticKind::MissingLocation);
+    }
+    let skipped = diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity >= Severity::Error);
+    let entry = loc.filter(|_| !skipped).map(|loc| SitemapEntry::<'a> {
+        location: loc,
+        last_modified: lastmod,
+    });
+    Report {
+        index,
+        entry,
+        diagnostics,
+    }
+}
@@ -0,0 +1,77 @@
+//! Discovery of sitemaps through the `Sitemap:` lines of a `robots.txt`.
+//!
+//! The sitemaps are given as [`SitemapEntry`]s, like the entries of a sitemap index.
+//! A `robots.txt` also authorizes sitemaps on other hosts to list the URLs of its host;
+//! see [`Robots::authorizes`].
+
+use crate::SitemapEntry;
+
+/// The `Sitemap:` lines of a `robots.txt`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Robots<'a> {
+    sitemaps: Vec<&'a str>,
+}
+impl<'a> Robots<'a> {
+    /// Parses the `robots.txt` `text`.
+    ///
+    /// `Sitemap:` lines are matched case-insensitively anywhere in the file,
+    /// regardless of the `User-agent:` groups. A byte order mark, CRLF line endings
+    /// and `#` comments are accepted. A `#` only starts a comment at the start of the line
+    /// or after whitespace, so fragments (`https://example.com/sitemap.xml#main`) are kept.
+    pub fn parse(text: &'a str) -> Self {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let sitemaps = text
+            .lines()
+            .filter_map(|line| {
+                let line = strip_comment(line);
+                let (key, value) = line.split_once(':')?;
+                let value = value.trim();
+                Some(value)
+                    .filter(|value| key.trim().eq_ignore_ascii_case("sitemap") && !value.is_empty())
+            })
+            .collect();
+        Self { sitemaps }
+    }
+    /// Returns the sitemaps, in the order of the file.
+    ///
+    /// They have no [`SitemapEntry::last_modified`].
+    /// As with sitemap indices, they can be sitemaps or sitemap indices.
+    pub fn sitemaps(&self) -> impl ExactSizeIterator<Item = SitemapEntry<'a>> + '_ {
+        self.sitemaps.iter().map(|location| SitemapEntry {
+            location,
+            last_modified: None,
+        })
+    }
+    /// Check if the sitemap at `sitemap` is listed, which authorizes it to list
+    /// the URLs of the host this `robots.txt` is from, even if it's on another host.
+    ///
+    /// The scheme and host are compared case-insensitively, and the rest as-is.
+    /// See [cross-submission](https://sitemaps.org/protocol.html#sitemaps_cross_submits).
+    pub fn authorizes(&self, sitemap: &str) -> bool {
+        let sitemap = split_origin(sitemap.trim());
+        self.sitemaps.iter().any(|listed| {
+            let listed = split_origin(listed);
+            listed.0.eq_ignore_ascii_case(sitemap.0) && listed.1 == sitemap.1
+        })
+    }
+}
+
+/// Remove the `#` comment of `line`.
+fn strip_comment(line: &str) -> &str {
+    let mut previous = None;
+    let start = line.char_indices().find_map(|(index, c)| {
+        let comment = c == '#' && previous.map_or(true, char::is_whitespace);
+        previous = Some(c);
+        Some(index).filter(|_| comment)
+    });
+    &line[..start.unwrap_or(line.len())]
+}
+
+/// Split `url` after the scheme and host.
+fn split_origin(url: &str) -> (&str, &str) {
+    let host_start = url.find("://").map_or(0, |index| index + 3);
+    let path_start = url[host_start..]
+        .find(|c| matches!(c, '/' | '?' | '#'))
+        .map_or(url.len(), |index| host_start + index);
+    url.split_at(path_start)
+}
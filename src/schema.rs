@@ -0,0 +1,390 @@
+//! Validation against the XML Schemas of [sitemaps](https://www.sitemaps.org/schemas/sitemap/0.9/sitemap.xsd)
+//! and [sitemap indices](https://www.sitemaps.org/schemas/sitemap/0.9/siteindex.xsd).
+//!
+//! The rules of the schemas are implemented natively; see [`crate::Document::validate_schema`].
+//! Unlike parsing, validation doesn't recover from anything: every [`Violation`] is
+//! a reason for a search engine to reject the file.
+//!
+//! Elements in other namespaces, which the schema of sitemaps allows at the end
+//! of a `<url>`, aren't validated.
+
+use crate::parse::LineIndex;
+use crate::{W3cDatetime, NAMESPACE};
+use roxmltree::Node;
+use std::fmt::{self, Display};
+
+/// The namespace of attributes allowed on any element, e.g. `xsi:schemaLocation`.
+const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+/// The minimum length of a `<loc>`, in characters.
+pub const MIN_LOCATION_LENGTH: usize = 12;
+/// The maximum length of a `<loc>`, in characters.
+pub const MAX_LOCATION_LENGTH: usize = 2048;
+/// The fields of a `<url>`, in the order of the schema.
+const URL_FIELDS: &[&str] = &["loc", "lastmod", "changefreq", "priority"];
+/// The fields of a `<sitemap>`, which can be in any order.
+const SITEMAP_FIELDS: &[&str] = &["loc", "lastmod"];
+const FREQUENCIES: &[&str] = &[
+    "always", "hourly", "daily", "weekly", "monthly", "yearly", "never",
+];
+
+/// The kind of rule of the schema which is violated.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ViolationKind {
+    /// The root element isn't a `<urlset>` or a `<sitemapindex>` in [`crate::NAMESPACE`].
+    ///
+    /// Nothing else is checked.
+    UnexpectedRoot {
+        name: String,
+        namespace: Option<String>,
+    },
+    /// An element isn't allowed where it is, e.g. an unknown element in [`crate::NAMESPACE`],
+    /// an element without a namespace, a second `<loc>`, or a `<lastmod>` after a `<priority>`.
+    ///
+    /// Its content isn't checked.
+    UnexpectedElement {
+        /// The local name of the parent.
+        parent: String,
+        name: String,
+        namespace: Option<String>,
+    },
+    /// A required element is missing, i.e. the `<loc>` of an entry, or the entries of the root.
+    MissingElement { parent: String, name: String },
+    /// An element has an attribute, other than in the `xsi` namespace.
+    UnexpectedAttribute {
+        element: String,
+        name: String,
+        namespace: Option<String>,
+    },
+    /// An element which only contains elements has text.
+    UnexpectedText { element: String },
+    /// The `<loc>` is shorter than [`MIN_LOCATION_LENGTH`] or longer than [`MAX_LOCATION_LENGTH`].
+    ///
+    /// Contains the number of characters, after collapsing whitespace.
+    LocationLength(usize),
+    /// The `<lastmod>` isn't an `xsd:date` or an `xsd:dateTime`.
+    ///
+    /// Those are stricter than [`W3cDatetime`]: a time must have seconds.
+    /// The time zone is optional.
+    InvalidLastModified(String),
+    /// The `<changefreq>` isn't one of the values of [`crate::Frequency`], in lowercase.
+    InvalidChangeFrequency(String),
+    /// The `<priority>` isn't a decimal number, e.g. it has an exponent.
+    InvalidPriority(String),
+    /// The `<priority>` isn't in the range `0.0..=1.0`.
+    PriorityOutOfRange(String),
+}
+impl Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedRoot {
+                name,
+                namespace: Some(namespace),
+            } => write!(
+                f,
+                "Expected <urlset> or <sitemapindex> in {NAMESPACE:?}, but found <{name}> in {namespace:?}."
+            ),
+            Self::UnexpectedRoot {
+                name,
+                namespace: None,
+            } => write!(
+                f,
+                "Expected <urlset> or <sitemapindex> in {NAMESPACE:?}, but found <{name}> without namespace."
+            ),
+            Self::UnexpectedElement {
+                parent,
+                name,
+                namespace: Some(namespace),
+            } => write!(
+                f,
+                "Unexpected <{name}> in namespace {namespace:?} in <{parent}>."
+            ),
+            Self::UnexpectedElement {
+                parent,
+                name,
+                namespace: None,
+            } => write!(f, "Unexpected <{name}> without namespace in <{parent}>."),
+            Self::MissingElement { parent, name } => {
+                write!(f, "Expected <{name}> in <{parent}>, but found none.")
+            }
+            Self::UnexpectedAttribute {
+                element,
+                name,
+                namespace: Some(namespace),
+            } => write!(
+                f,
+                "Unexpected attribute {name:?} in namespace {namespace:?} on <{element}>."
+            ),
+            Self::UnexpectedAttribute {
+                element,
+                name,
+                namespace: None,
+            } => write!(f, "Unexpected attribute {name:?} on <{element}>."),
+            Self::UnexpectedText { element } => write!(f, "Unexpected text in <{element}>."),
+            Self::LocationLength(length) => write!(
+                f,
+                "<loc> has {length} characters. Expected {MIN_LOCATION_LENGTH} to {MAX_LOCATION_LENGTH}."
+            ),
+            Self::InvalidLastModified(text) => write!(
+                f,
+                "<lastmod> has invalid format: {text:?}. Expected xsd:date or xsd:dateTime."
+            ),
+            Self::InvalidChangeFrequency(text) => {
+                write!(f, "<changefreq> has invalid format: {text:?}")
+            }
+            Self::InvalidPriority(text) => write!(
+                f,
+                "<priority> has invalid format: {text:?}. Expected decimal number."
+            ),
+            Self::PriorityOutOfRange(text) => write!(f, "<priority> {text} is out of range"),
+        }
+    }
+}
+/// A violation of the schema.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Violation {
+    /// The position in the text of the offending node.
+    pub position: crate::TextPos,
+    pub kind: ViolationKind,
+}
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.position, self.kind)
+    }
+}
+
+/// A [`ViolationKind`] at a byte offset, before its [`Violation::position`] is computed.
+type Found = (usize, ViolationKind);
+
+/// Check `doc`, whose line starts are `lines`, against the schemas.
+pub(crate) fn validate(doc: &roxmltree::Document<'_>, lines: &LineIndex) -> Vec<Violation> {
+    find_violations(doc)
+        .into_iter()
+        .map(|(offset, kind)| Violation {
+            position: lines.position(doc.input_text(), offset),
+            kind,
+        })
+        .collect()
+}
+fn find_violations(doc: &roxmltree::Document<'_>) -> Vec<Found> {
+    let mut violations = Vec::new();
+    let root = doc.root_element();
+    let (entry, urlset) = match (namespace(root), root.tag_name().name()) {
+        (Some(NAMESPACE), "urlset") => ("url", true),
+        (Some(NAMESPACE), "sitemapindex") => ("sitemap", false),
+        (namespace, name) => {
+            violations.push(violation(
+                root,
+                ViolationKind::UnexpectedRoot {
+                    name: name.to_owned(),
+                    namespace: namespace.map(str::to_owned),
+                },
+            ));
+            return violations;
+        }
+    };
+    check_attributes(root, &mut violations);
+    check_no_text(root, &mut violations);
+    let mut entries = 0;
+    for child in root.children().filter(Node::is_element) {
+        if namespace(child) == Some(NAMESPACE) && child.tag_name().name() == entry {
+            entries += 1;
+            validate_entry(child, urlset, &mut violations);
+        } else {
+            violations.push(unexpected_element(root, child));
+        }
+    }
+    if entries == 0 {
+        violations.push(violation(
+            root,
+            ViolationKind::MissingElement {
+                parent: root.tag_name().name().to_owned(),
+                name: entry.to_owned(),
+            },
+        ));
+    }
+    violations
+}
+/// Check a `<url>`, whose fields are ordered and followed by elements of other namespaces,
+/// or a `<sitemap>`, whose fields can be in any order.
+fn validate_entry(node: Node<'_, '_>, url: bool, violations: &mut Vec<Found>) {
+    let fields = if url { URL_FIELDS } else { SITEMAP_FIELDS };
+    check_attributes(node, violations);
+    check_no_text(node, violations);
+    let mut seen = [false; 4];
+    // The index of the first field allowed next in a `<url>`.
+    let mut next = 0;
+    let mut foreign = false;
+    for child in node.children().filter(Node::is_element) {
+        match namespace(child) {
+            Some(NAMESPACE) => {
+                let allowed = fields
+                    .iter()
+                    .position(|field| *field == child.tag_name().name())
+                    .filter(|&index| !seen[index] && (!url || (!foreign && index >= next)));
+                if let Some(index) = allowed {
+                    seen[index] = true;
+                    next = index + 1;
+                    validate_field(child, violations);
+                } else {
+                    violations.push(unexpected_element(node, child));
+                }
+            }
+            Some(_) if url => foreign = true,
+            _ => violations.push(unexpected_element(node, child)),
+        }
+    }
+    if !seen[0] {
+        violations.push(violation(
+            node,
+            ViolationKind::MissingElement {
+                parent: node.tag_name().name().to_owned(),
+                name: "loc".to_owned(),
+            },
+        ));
+    }
+}
+fn validate_field(node: Node<'_, '_>, violations: &mut Vec<Found>) {
+    check_attributes(node, violations);
+    for child in node.children().filter(Node::is_element) {
+        violations.push(unexpected_element(node, child));
+    }
+    let text: String = node
+        .children()
+        .filter(Node::is_text)
+        .filter_map(|child| child.text())
+        .collect();
+    let kind = match node.tag_name().name() {
+        "loc" => {
+            let length = collapse_whitespace(&text).chars().count();
+            Some(ViolationKind::LocationLength(length))
+                .filter(|_| !(MIN_LOCATION_LENGTH..=MAX_LOCATION_LENGTH).contains(&length))
+        }
+        "lastmod" => Some(ViolationKind::InvalidLastModified(text.clone()))
+            .filter(|_| !is_date_or_datetime(text.trim_matches(is_xml_whitespace))),
+        // Enumerations of `xsd:string` don't allow surrounding whitespace.
+        "changefreq" => Some(ViolationKind::InvalidChangeFrequency(text.clone()))
+            .filter(|_| !FREQUENCIES.contains(&text.as_str())),
+        _ => {
+            let trimmed = text.trim_matches(is_xml_whitespace);
+            if !is_decimal(trimmed) {
+                Some(ViolationKind::InvalidPriority(text.clone()))
+            } else if !matches!(trimmed.parse::<f64>(), Ok(num) if (0.0..=1.0).contains(&num)) {
+                Some(ViolationKind::PriorityOutOfRange(trimmed.to_owned()))
+            } else {
+                None
+            }
+        }
+    };
+    violations.extend(kind.map(|kind| violation(node, kind)));
+}
+
+/// The namespace of `node`, which is [`None`] after `xmlns=""`.
+fn namespace<'a>(node: Node<'a, '_>) -> Option<&'a str> {
+    node.tag_name()
+        .namespace()
+        .filter(|namespace| !namespace.is_empty())
+}
+fn violation(node: Node<'_, '_>, kind: ViolationKind) -> Found {
+    (node.range().start, kind)
+}
+fn unexpected_element(parent: Node<'_, '_>, node: Node<'_, '_>) -> Found {
+    violation(
+        node,
+        ViolationKind::UnexpectedElement {
+            parent: parent.tag_name().name().to_owned(),
+            name: node.tag_name().name().to_owned(),
+            namespace: namespace(node).map(str::to_owned),
+        },
+    )
+}
+fn check_attributes(node: Node<'_, '_>, violations: &mut Vec<Found>) {
+    for attribute in node.attributes() {
+        if attribute.namespace() != Some(XSI_NAMESPACE) {
+            violations.push(violation(
+                node,
+                ViolationKind::UnexpectedAttribute {
+                    element: node.tag_name().name().to_owned(),
+                    name: attribute.name().to_owned(),
+                    namespace: attribute.namespace().map(str::to_owned),
+                },
+            ));
+        }
+    }
+}
+/// Report the text, other than whitespace, in an element which only contains elements.
+fn check_no_text(node: Node<'_, '_>, violations: &mut Vec<Found>) {
+    for child in node.children().filter(Node::is_text) {
+        if !child
+            .text()
+            .unwrap_or_default()
+            .chars()
+            .all(is_xml_whitespace)
+        {
+            violations.push(violation(
+                child,
+                ViolationKind::UnexpectedText {
+                    element: node.tag_name().name().to_owned(),
+                },
+            ));
+        }
+    }
+}
+
+fn is_xml_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r')
+}
+/// Apply the `collapse` whitespace facet of XML Schema.
+fn collapse_whitespace(text: &str) -> String {
+    text.split(is_xml_whitespace)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+/// Check if `text` is an `xsd:decimal`, i.e. digits with an optional sign and fraction.
+fn is_decimal(text: &str) -> bool {
+    let unsigned = text.strip_prefix(|c| c == '+' || c == '-').unwrap_or(text);
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, fraction),
+        None => (unsigned, ""),
+    };
+    !(integer.is_empty() && fraction.is_empty())
+        && integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+}
+/// Check if `text` is an `xsd:date` or an `xsd:dateTime` with a four-digit year.
+fn is_date_or_datetime(text: &str) -> bool {
+    // Both have an optional time zone, which W3C Datetime requires with a time.
+    let (datetime, zone) = if let Some(datetime) = text.strip_suffix('Z') {
+        (datetime, "Z")
+    } else if text.len() > 6 && text.is_char_boundary(text.len() - 6) {
+        let (datetime, zone) = text.split_at(text.len() - 6);
+        if (zone.starts_with('+') || zone.starts_with('-')) && zone.as_bytes()[3] == b':' {
+            (datetime, zone)
+        } else {
+            (text, "Z")
+        }
+    } else {
+        (text, "Z")
+    };
+    if zone != "Z" {
+        let bytes = zone.as_bytes();
+        let digit = |index: usize| u32::from(bytes[index].wrapping_sub(b'0'));
+        let valid_zone = [1, 2, 4, 5]
+            .iter()
+            .all(|&index| bytes[index].is_ascii_digit())
+            && digit(4) * 10 + digit(5) < 60
+            && (digit(1) * 10 + digit(2)) * 60 + digit(4) * 10 + digit(5) <= 14 * 60;
+        if !valid_zone {
+            return false;
+        }
+    }
+    if datetime.len() == 10 {
+        W3cDatetime::parse(datetime).is_ok()
+    } else {
+        // Seconds are required, but `24:00:00` isn't supported.
+        datetime.as_bytes().get(16) == Some(&b':')
+            && W3cDatetime::parse(&format!("{datetime}Z")).is_ok()
+    }
+}
@@ -0,0 +1,81 @@
+//! Checks of the [location of sitemaps](https://sitemaps.org/protocol.html#location), with the `url` feature.
+//!
+//! A sitemap may only list URLs on the same scheme and host, under the directory of the sitemap.
+//! E.g. `http://example.com/catalog/sitemap.xml` may list `http://example.com/catalog/shoes`,
+//! but not `http://example.com/images/` or `https://example.com/catalog/shoes`.
+//! Search engines ignore the other entries, unless the sitemap is
+//! cross-submitted in the `robots.txt` of their host; see [`classify_with_robots`].
+
+use crate::location::{self, LocationError, Url};
+use crate::robots::Robots;
+use std::fmt::{self, Display};
+
+/// Where a location is, compared to the sitemap listing it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Scope {
+    /// The location is allowed in the sitemap.
+    InScope,
+    /// The location is on the same scheme and host,
+    /// but not under the directory of the sitemap.
+    OutOfPath,
+    /// The location is on another host or port.
+    CrossHost,
+    /// The location is on the same host, but another scheme (e.g. `https` instead of `http`).
+    SchemeMismatch,
+}
+impl Scope {
+    /// Check if this is [`Scope::InScope`].
+    pub fn is_in_scope(&self) -> bool {
+        *self == Self::InScope
+    }
+}
+impl Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InScope => "in scope",
+            Self::OutOfPath => "outside the directory of the sitemap",
+            Self::CrossHost => "on another host than the sitemap",
+            Self::SchemeMismatch => "on another scheme than the sitemap",
+        })
+    }
+}
+
+/// Get the [`Scope`] of `location` in the sitemap fetched from `sitemap`.
+///
+/// A location on another host and scheme is [`Scope::CrossHost`].
+pub fn classify(sitemap: &Url, location: &Url) -> Scope {
+    if location.host_str() != sitemap.host_str() {
+        Scope::CrossHost
+    } else if location.scheme() != sitemap.scheme() {
+        Scope::SchemeMismatch
+    } else if location.port() != sitemap.port() {
+        Scope::CrossHost
+    } else if !location.path().starts_with(directory(sitemap.path())) {
+        Scope::OutOfPath
+    } else {
+        Scope::InScope
+    }
+}
+/// Get the [`Scope`] of `location` in the sitemap fetched from `sitemap`,
+/// given the `robots.txt` of the host of `location`.
+///
+/// If `robots` [authorizes](Robots::authorizes) the sitemap, the location is [`Scope::InScope`]
+/// wherever the sitemap is. Otherwise, this is the same as [`classify`].
+pub fn classify_with_robots(sitemap: &Url, location: &Url, robots: &Robots<'_>) -> Scope {
+    if robots.authorizes(sitemap.as_str()) {
+        Scope::InScope
+    } else {
+        classify(sitemap, location)
+    }
+}
+/// Parses `location` using [`location::parse`] and [`classify`]s it.
+///
+/// Use this on [`crate::UrlEntry::location`] or [`crate::SitemapEntry::location`].
+pub fn classify_location(sitemap: &Url, location: &str) -> Result<Scope, LocationError> {
+    location::parse(location).map(|location| classify(sitemap, &location))
+}
+
+/// The path up to and including the last `/`.
+fn directory(path: &str) -> &str {
+    path.rfind('/').map_or("/", |index| &path[..=index])
+}
@@ -0,0 +1,672 @@
+//! A streaming parser, reading entries one at a time from a [`BufRead`].
+//!
+//! Unlike [`crate::Document`], this never holds more than one entry in memory,
+//! so it's well suited for large sitemaps.
+
+use crate::parse::{check_limits, check_news_limit, parse_sitemap_entry, parse_url_entry, Element};
+use crate::{
+    news, DocumentKind, ExtensionAttribute, NamespaceMode, OwnedSitemapEntry, OwnedUrlEntry,
+    ParseOptions, Report, Severity, SitemapEntry, TextPos, UrlEntry,
+};
+use quick_xml::encoding::Decoder;
+use quick_xml::events::Event;
+use quick_xml::name::ResolveResult;
+use quick_xml::NsReader;
+use std::fmt::{self, Debug};
+use std::io::{self, BufRead, BufReader, Read};
+use std::ops::Range;
+
+/// An error from a [`StreamParser`].
+#[derive(Debug)]
+pub enum Error {
+    /// The XML is malformed, or reading from the underlying reader failed.
+    Xml(quick_xml::Error),
+    /// The input ended before the root element was closed.
+    UnexpectedEof,
+    /// The root element isn't of the kind requested, or an entry was rejected.
+    ///
+    /// Contains [`crate::Error::UrlsetMissing`], [`crate::Error::SitemapIndexMissing`],
+    /// [`crate::Error::UnexpectedNamespace`], [`crate::Error::Rejected`],
+    /// or [`crate::Error::TooLarge`] when the [`ParseOptions::max_size`] is reached.
+    /// See [`StreamParser::kind`].
+    Document(crate::Error),
+}
+impl From<quick_xml::Error> for Error {
+    fn from(err: quick_xml::Error) -> Self {
+        Self::Xml(err)
+    }
+}
+
+/// Keeps track of the [`TextPos`] of the consumed bytes,
+/// and hides the bytes past the [`ParseOptions::max_size`].
+#[derive(Debug)]
+struct PositionTracker<R> {
+    reader: R,
+    /// The number of bytes consumed.
+    consumed: u64,
+    max_size: u64,
+    row: u32,
+    col: u32,
+    /// The position of the last consumed `<`, which is the start of the last tag.
+    tag_start: TextPos,
+    /// The bytes consumed since this was last cleared.
+    raw: Vec<u8>,
+    /// The index in [`Self::raw`] of the last consumed `<`.
+    raw_tag_start: usize,
+}
+impl<R: BufRead> PositionTracker<R> {
+    /// Check if there are bytes past the [`Self::max_size`].
+    fn truncated(&mut self) -> bool {
+        self.consumed >= self.max_size
+            && self.reader.fill_buf().map_or(false, |buf| !buf.is_empty())
+    }
+}
+impl<R: BufRead> BufRead for PositionTracker<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let remaining =
+            usize::try_from(self.max_size.saturating_sub(self.consumed)).unwrap_or(usize::MAX);
+        let buf = self.reader.fill_buf()?;
+        Ok(&buf[..buf.len().min(remaining)])
+    }
+    fn consume(&mut self, amt: usize) {
+        self.consumed += amt as u64;
+        if let Ok(buf) = self.reader.fill_buf() {
+            let buf = &buf[..amt.min(buf.len())];
+            for (index, &byte) in buf.iter().enumerate() {
+                if byte == b'<' {
+                    self.tag_start = TextPos::new(self.row, self.col);
+                    self.raw_tag_start = self.raw.len() + index;
+                }
+                if byte == b'\n' {
+                    self.row += 1;
+                    self.col = 1;
+                // Count characters, not UTF-8 continuation bytes.
+                } else if byte & 0b1100_0000 != 0b1000_0000 {
+                    self.col += 1;
+                }
+            }
+            self.raw.extend_from_slice(buf);
+        }
+        self.reader.consume(amt)
+    }
+}
+// Required by `BufRead`, but never used by `quick_xml`.
+impl<R: BufRead> Read for PositionTracker<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.fill_buf()?.read(buf)?;
+        self.consume(read);
+        Ok(read)
+    }
+}
+
+/// The elements of one entry, stored in a flat list in document order.
+#[derive(Debug, Default)]
+struct Tree {
+    text: String,
+    elements: Vec<RawElement>,
+    attributes: Vec<RawAttribute>,
+}
+#[derive(Debug)]
+struct RawElement {
+    name: Range<usize>,
+    namespace: Option<Range<usize>>,
+    text: Option<Range<usize>>,
+    /// The range in [`Tree::attributes`].
+    attributes: Range<usize>,
+    /// The index after the last descendant of this element.
+    end: usize,
+    /// The range in [`Tree::text`] of the source between the start and end tags.
+    inner: Range<usize>,
+    position: TextPos,
+}
+#[derive(Debug)]
+struct RawAttribute {
+    name: Range<usize>,
+    namespace: Option<Range<usize>>,
+    value: Range<usize>,
+}
+impl Tree {
+    fn clear(&mut self) {
+        self.text.clear();
+        self.elements.clear();
+        self.attributes.clear();
+    }
+    fn root(&self) -> Node<'_> {
+        Node {
+            tree: self,
+            index: 0,
+        }
+    }
+}
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Node<'a> {
+    tree: &'a Tree,
+    index: usize,
+}
+impl<'a> Node<'a> {
+    fn element(&self) -> &'a RawElement {
+        &self.tree.elements[self.index]
+    }
+}
+impl<'a> Element<'a> for Node<'a> {
+    type Children = Children<'a>;
+
+    fn name(&self) -> &'a str {
+        &self.tree.text[self.element().name.clone()]
+    }
+    fn namespace(&self) -> Option<&'a str> {
+        self.element()
+            .namespace
+            .clone()
+            .map(|range| &self.tree.text[range])
+    }
+    fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.tree.attributes[self.element().attributes.clone()]
+            .iter()
+            .find(|attribute| {
+                attribute.namespace.is_none() && &self.tree.text[attribute.name.clone()] == name
+            })
+            .map(|attribute| &self.tree.text[attribute.value.clone()])
+    }
+    fn attributes(&self) -> Vec<ExtensionAttribute<'a>> {
+        self.tree.attributes[self.element().attributes.clone()]
+            .iter()
+            .map(|attribute| ExtensionAttribute {
+                namespace: attribute
+                    .namespace
+                    .clone()
+                    .map(|range| &self.tree.text[range]),
+                name: &self.tree.text[attribute.name.clone()],
+                value: &self.tree.text[attribute.value.clone()],
+            })
+            .collect()
+    }
+    fn text(&self) -> Option<&'a str> {
+        self.element()
+            .text
+            .clone()
+            .map(|range| &self.tree.text[range])
+    }
+    fn inner_xml(&self) -> &'a str {
+        &self.tree.text[self.element().inner.clone()]
+    }
+    fn children(&self) -> Self::Children {
+        Children {
+            tree: self.tree,
+            next: self.index + 1,
+            end: self.element().end,
+        }
+    }
+    fn position(&self) -> TextPos {
+        self.element().position
+    }
+}
+#[derive(Debug, Clone)]
+pub(crate) struct Children<'a> {
+    tree: &'a Tree,
+    next: usize,
+    end: usize,
+}
+impl<'a> Iterator for Children<'a> {
+    type Item = Node<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let node = Node {
+            tree: self.tree,
+            index: self.next,
+        };
+        self.next = self.tree.elements[self.next].end;
+        Some(node)
+    }
+}
+
+/// Returns [`crate::Error::Rejected`] if `report` has a [`Severity::Fatal`] problem.
+fn check_fatal<T>(report: Report<T>) -> Result<Report<T>, Error> {
+    match report
+        .diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.severity == Severity::Fatal)
+    {
+        Some(diagnostic) => Err(Error::Document(crate::Error::Rejected(diagnostic.clone()))),
+        None => Ok(report),
+    }
+}
+/// Decodes `bytes` and appends them to `text`.
+fn push_text(text: &mut String, decoder: Decoder, bytes: &[u8]) -> Result<Range<usize>, Error> {
+    let start = text.len();
+    text.push_str(&decoder.decode(bytes)?);
+    Ok(start..text.len())
+}
+fn push_namespace(
+    text: &mut String,
+    decoder: Decoder,
+    namespace: ResolveResult,
+) -> Result<Option<Range<usize>>, Error> {
+    if let ResolveResult::Bound(namespace) = namespace {
+        push_text(text, decoder, namespace.as_ref()).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+enum Token {
+    /// The ranges in [`Tree::text`] of the name and namespace,
+    /// and the range in [`Tree::attributes`].
+    Start(Range<usize>, Option<Range<usize>>, Range<usize>),
+    End,
+    /// The range in [`Tree::text`].
+    Text(Range<usize>),
+    Eof,
+    Other,
+}
+
+/// A pull-based parser of sitemaps, reading from a [`BufRead`].
+///
+/// Entries are returned one at a time, borrowing from the parser,
+/// so the memory usage is bounded by the size of the largest entry.
+///
+/// The same validation as in [`crate::Document::iterate`] is applied.
+pub struct StreamParser<R> {
+    reader: NsReader<PositionTracker<R>>,
+    buf: Vec<u8>,
+    tree: Tree,
+    /// The kind of the root element, regardless of its namespace.
+    kind: Option<DocumentKind>,
+    /// The namespace of the root element.
+    namespace: Option<String>,
+    options: ParseOptions,
+    index: usize,
+    /// The number of `<url>` with a `<news:news>` read.
+    news_entries: usize,
+    finished: bool,
+}
+impl<R> Debug for StreamParser<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamParser")
+            .field("kind", &self.kind)
+            .field("index", &self.index)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+impl<R: Read> StreamParser<BufReader<R>> {
+    /// Wraps `reader` in a [`BufReader`] and calls [`StreamParser::new`].
+    pub fn from_read(reader: R) -> Result<Self, Error> {
+        Self::new(BufReader::new(reader))
+    }
+}
+impl<R: BufRead> StreamParser<R> {
+    /// Reads from `reader` until the root element is found.
+    ///
+    /// See [`StreamParser::kind`] to get what kind of sitemap it is.
+    pub fn new(reader: R) -> Result<Self, Error> {
+        Self::with_options(reader, ParseOptions::default())
+    }
+    /// Like [`StreamParser::new`], with `options`.
+    ///
+    /// Unlike [`StreamParser::options`], the [`ParseOptions::max_size`]
+    /// also applies while looking for the root element.
+    pub fn with_options(reader: R, options: ParseOptions) -> Result<Self, Error> {
+        let mut reader = NsReader::from_reader(PositionTracker {
+            reader,
+            consumed: 0,
+            max_size: options.max_size.unwrap_or(u64::MAX),
+            row: 1,
+            col: 1,
+            tag_start: TextPos::new(1, 1),
+            raw: Vec::new(),
+            raw_tag_start: 0,
+        });
+        reader.expand_empty_elements(true);
+        let mut me = Self {
+            reader,
+            buf: Vec::new(),
+            tree: Tree::default(),
+            kind: None,
+            namespace: None,
+            options,
+            index: 0,
+            news_entries: 0,
+            finished: false,
+        };
+        loop {
+            match me.next_token()? {
+                Token::Start(name, namespace, _) => {
+                    me.kind = match &me.tree.text[name] {
+                        "urlset" => Some(DocumentKind::Urlset),
+                        "sitemapindex" => Some(DocumentKind::SitemapIndex),
+                        _ => None,
+                    };
+                    me.namespace = namespace.map(|range| me.tree.text[range].to_owned());
+                    break;
+                }
+                Token::Eof => return Err(Error::UnexpectedEof),
+                Token::End | Token::Text(_) | Token::Other => {}
+            }
+            me.tree.clear();
+        }
+        me.tree.clear();
+        Ok(me)
+    }
+    /// Returns the kind of this document, based on the root element.
+    ///
+    /// Returns [`None`] if the root element is neither a `<urlset>` nor a `<sitemapindex>`,
+    /// or isn't in a namespace accepted by the [`NamespaceMode`].
+    pub fn kind(&self) -> Option<DocumentKind> {
+        self.kind
+            .filter(|_| self.options.namespace_mode.accepts(self.namespace()))
+    }
+    /// Set how problems in the entries are handled.
+    ///
+    /// Problems with [`Severity::Fatal`] make the methods reading entries return
+    /// [`crate::Error::Rejected`] when they are reached.
+    pub fn options(mut self, options: ParseOptions) -> Self {
+        self.reader.get_mut().max_size = options.max_size.unwrap_or(u64::MAX);
+        self.options = options;
+        self
+    }
+    /// Set how namespaces are checked.
+    ///
+    /// Shorthand for [`ParseOptions::namespace_mode`].
+    pub fn namespace_mode(mut self, namespace_mode: NamespaceMode) -> Self {
+        self.options.namespace_mode = namespace_mode;
+        self
+    }
+    /// Returns the namespace of the root element.
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+    /// Returns the next [`UrlEntry`], or [`None`] if the `<urlset>` is finished.
+    ///
+    /// Invalid entries are skipped.
+    /// Uses [`log`] for logging errors in the XML.
+    /// See [`StreamParser::next_entry_with_diagnostics`] to get them as data.
+    pub fn next_entry(&mut self) -> Result<Option<UrlEntry<'_>>, Error> {
+        self.expect_kind(DocumentKind::Urlset)?;
+        loop {
+            if !self.read_url()? {
+                return Ok(None);
+            }
+            let report = check_fatal(self.url_report())?;
+            let valid = report.entry.is_some();
+            report.log();
+            if valid {
+                break;
+            }
+        }
+        // Parse again, as the borrow of the report can't be returned from the loop.
+        // Entries are small, so this is cheap.
+        Ok(self.url_report().entry)
+    }
+    /// Returns a [`Report`] for the next `<url>`, or [`None`] if the `<urlset>` is finished.
+    ///
+    /// The [`Report::diagnostics`] contain all the problems found in the entry.
+    pub fn next_entry_with_diagnostics(&mut self) -> Result<Option<Report<UrlEntry<'_>>>, Error> {
+        self.expect_kind(DocumentKind::Urlset)?;
+        if !self.read_url()? {
+            return Ok(None);
+        }
+        check_fatal(self.url_report()).map(Some)
+    }
+    /// Returns the next [`SitemapEntry`], or [`None`] if the `<sitemapindex>` is finished.
+    ///
+    /// Invalid entries are skipped.
+    /// Uses [`log`] for logging errors in the XML.
+    /// See [`StreamParser::next_sitemap_with_diagnostics`] to get them as data.
+    pub fn next_sitemap(&mut self) -> Result<Option<SitemapEntry<'_>>, Error> {
+        self.expect_kind(DocumentKind::SitemapIndex)?;
+        loop {
+            if !self.read_entry()? {
+                return Ok(None);
+            }
+            let report = check_fatal(self.sitemap_report())?;
+            let valid = report.entry.is_some();
+            report.log();
+            if valid {
+                break;
+            }
+        }
+        // See `Self::next_entry`.
+        Ok(self.sitemap_report().entry)
+    }
+    /// Returns a [`Report`] for the next `<sitemap>`,
+    /// or [`None`] if the `<sitemapindex>` is finished.
+    ///
+    /// The [`Report::diagnostics`] contain all the problems found in the entry.
+    pub fn next_sitemap_with_diagnostics(
+        &mut self,
+    ) -> Result<Option<Report<SitemapEntry<'_>>>, Error> {
+        self.expect_kind(DocumentKind::SitemapIndex)?;
+        if !self.read_entry()? {
+            return Ok(None);
+        }
+        check_fatal(self.sitemap_report()).map(Some)
+    }
+
+    /// Turns this into an [`Iterator`] of [`OwnedUrlEntry`], using [`StreamParser::next_entry`].
+    pub fn into_owned_entries(self) -> OwnedEntries<R> {
+        OwnedEntries { parser: self }
+    }
+    /// Turns this into an [`Iterator`] of [`OwnedSitemapEntry`], using [`StreamParser::next_sitemap`].
+    pub fn into_owned_sitemaps(self) -> OwnedSitemaps<R> {
+        OwnedSitemaps { parser: self }
+    }
+
+    fn expect_kind(&self, kind: DocumentKind) -> Result<(), Error> {
+        if self.kind != Some(kind) {
+            Err(Error::Document(match kind {
+                DocumentKind::Urlset => crate::Error::UrlsetMissing,
+                DocumentKind::SitemapIndex => crate::Error::SitemapIndexMissing,
+            }))
+        } else if !self.options.namespace_mode.accepts(self.namespace()) {
+            Err(Error::Document(crate::Error::UnexpectedNamespace(
+                self.namespace.clone(),
+            )))
+        } else {
+            Ok(())
+        }
+    }
+    /// Reads the next `<url>` using [`Self::read_entry`], counting the news entries.
+    fn read_url(&mut self) -> Result<bool, Error> {
+        let read = self.read_entry()?;
+        if read && news::contains_news(self.tree.root()) {
+            self.news_entries += 1;
+        }
+        Ok(read)
+    }
+    /// Parses the `<url>` in [`Self::tree`].
+    fn url_report(&self) -> Report<UrlEntry<'_>> {
+        let root = self.tree.root();
+        let mut report = parse_url_entry(self.index - 1, root, &self.options);
+        check_news_limit(&mut report, root, self.news_entries > news::MAX_ENTRIES);
+        check_limits(
+            &mut report,
+            self.index - 1,
+            || root.position(),
+            self.reader.get_ref().consumed,
+        );
+        report
+    }
+    /// Parses the `<sitemap>` in [`Self::tree`].
+    fn sitemap_report(&self) -> Report<SitemapEntry<'_>> {
+        let root = self.tree.root();
+        let mut report = parse_sitemap_entry(self.index - 1, root, &self.options);
+        check_limits(
+            &mut report,
+            self.index - 1,
+            || root.position(),
+            self.reader.get_ref().consumed,
+        );
+        report
+    }
+    /// Reads the next child of the root element into [`Self::tree`].
+    ///
+    /// Returns `false` if the root element is closed,
+    /// or the [`ParseOptions::max_entries`] are read.
+    fn read_entry(&mut self) -> Result<bool, Error> {
+        if self.finished
+            || self
+                .options
+                .max_entries
+                .map_or(false, |max| self.index >= max)
+        {
+            return Ok(false);
+        }
+        self.tree.clear();
+        self.reader.get_mut().raw.clear();
+        let mut stack = Vec::new();
+        loop {
+            match self.next_token()? {
+                Token::Start(name, namespace, attributes) => {
+                    let tracker = self.reader.get_ref();
+                    stack.push(self.tree.elements.len());
+                    self.tree.elements.push(RawElement {
+                        name,
+                        namespace,
+                        attributes,
+                        text: None,
+                        end: 0,
+                        inner: tracker.raw.len()..tracker.raw.len(),
+                        position: tracker.tag_start,
+                    });
+                }
+                Token::End => {
+                    if let Some(index) = stack.pop() {
+                        let end = self.tree.elements.len();
+                        let element = &mut self.tree.elements[index];
+                        element.end = end;
+                        // Empty elements are expanded without consuming an end tag.
+                        element.inner.end =
+                            self.reader.get_ref().raw_tag_start.max(element.inner.start);
+                        if stack.is_empty() {
+                            self.push_source()?;
+                            self.index += 1;
+                            return Ok(true);
+                        }
+                    } else {
+                        self.finished = true;
+                        return Ok(false);
+                    }
+                }
+                Token::Text(range) => {
+                    if let Some(&current) = stack.last() {
+                        let has_children = self.tree.elements.len() > current + 1;
+                        let element = &mut self.tree.elements[current];
+                        match &mut element.text {
+                            None if !has_children => element.text = Some(range),
+                            // Adjacent text and CDATA.
+                            Some(text) if text.end == range.start && !has_children => {
+                                text.end = range.end
+                            }
+                            _ => {}
+                        }
+                    } else {
+                        // Whitespace between entries.
+                        self.tree.text.truncate(range.start);
+                    }
+                }
+                Token::Eof => return Err(Error::UnexpectedEof),
+                Token::Other => {}
+            }
+        }
+    }
+    /// Appends the source of the entry to [`Tree::text`],
+    /// making [`RawElement::inner`] relative to it.
+    fn push_source(&mut self) -> Result<(), Error> {
+        let decoder = self.reader.decoder();
+        let source = push_text(&mut self.tree.text, decoder, &self.reader.get_ref().raw)?;
+        for element in &mut self.tree.elements {
+            element.inner.start += source.start;
+            element.inner.end += source.start;
+        }
+        Ok(())
+    }
+    /// Reads the next event, appending any names and text to [`Self::tree`].
+    fn next_token(&mut self) -> Result<Token, Error> {
+        self.buf.clear();
+        let start = self.tree.text.len();
+        let event = self.reader.read_event_into(&mut self.buf);
+        if matches!(event, Ok(Event::Eof) | Err(_)) && self.reader.get_mut().truncated() {
+            return Err(Error::Document(crate::Error::TooLarge(
+                self.reader.get_ref().max_size,
+            )));
+        }
+        let token = match event? {
+            Event::Start(start_tag) => {
+                let decoder = self.reader.decoder();
+                let text = &mut self.tree.text;
+                let (namespace, name) = self.reader.resolve_element(start_tag.name());
+                let name = push_text(text, decoder, name.as_ref())?;
+                let namespace = push_namespace(text, decoder, namespace)?;
+                let attributes_start = self.tree.attributes.len();
+                for attribute in start_tag.attributes() {
+                    let attribute = attribute.map_err(quick_xml::Error::from)?;
+                    // Namespace declarations aren't attributes.
+                    if attribute.key.as_namespace_binding().is_some() {
+                        continue;
+                    }
+                    let (namespace, name) = self.reader.resolve_attribute(attribute.key);
+                    let name = push_text(text, decoder, name.as_ref())?;
+                    let namespace = push_namespace(text, decoder, namespace)?;
+                    let value = attribute.decode_and_unescape_value(&self.reader)?;
+                    let start = text.len();
+                    text.push_str(&value);
+                    self.tree.attributes.push(RawAttribute {
+                        name,
+                        namespace,
+                        value: start..text.len(),
+                    });
+                }
+                Token::Start(
+                    name,
+                    namespace,
+                    attributes_start..self.tree.attributes.len(),
+                )
+            }
+            Event::End(_) => Token::End,
+            Event::Text(text) => {
+                self.tree.text.push_str(&text.unescape()?);
+                Token::Text(start..self.tree.text.len())
+            }
+            Event::CData(cdata) => {
+                let decoder = self.reader.decoder();
+                self.tree.text.push_str(&decoder.decode(&cdata)?);
+                Token::Text(start..self.tree.text.len())
+            }
+            Event::Eof => Token::Eof,
+            _ => Token::Other,
+        };
+        Ok(token)
+    }
+}
+
+/// An [`Iterator`] of [`OwnedUrlEntry`], created by [`StreamParser::into_owned_entries`].
+#[derive(Debug)]
+pub struct OwnedEntries<R> {
+    parser: StreamParser<R>,
+}
+impl<R: BufRead> Iterator for OwnedEntries<R> {
+    type Item = Result<OwnedUrlEntry, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parser
+            .next_entry()
+            .map(|entry| entry.map(OwnedUrlEntry::from))
+            .transpose()
+    }
+}
+/// An [`Iterator`] of [`OwnedSitemapEntry`], created by [`StreamParser::into_owned_sitemaps`].
+#[derive(Debug)]
+pub struct OwnedSitemaps<R> {
+    parser: StreamParser<R>,
+}
+impl<R: BufRead> Iterator for OwnedSitemaps<R> {
+    type Item = Result<OwnedSitemapEntry, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parser
+            .next_sitemap()
+            .map(|entry| entry.map(OwnedSitemapEntry::from))
+            .transpose()
+    }
+}
@@ -0,0 +1,141 @@
+//! Parsing of [text sitemaps](https://sitemaps.org/protocol.html#otherformats) (`sitemap.txt`),
+//! with one URL per line.
+
+use crate::parse::{check_limits, location_problems};
+use crate::{
+    check_fatal, check_size, from_utf8, Diagnostic, DiagnosticKind, Error, ParseOptions, Report,
+    Severity, TextPos, UrlEntry,
+};
+use std::fmt::Debug;
+
+/// A text sitemap, where every line is the location of a [`UrlEntry`].
+///
+/// The entries only have a [`UrlEntry::location`]. Surrounding whitespace
+/// of the lines is trimmed, and a byte order mark is ignored.
+/// There's a [`Report`] for every line, so [`Report::index`] is the line number, starting at `0`.
+///
+/// The same checks as for the `<loc>` of [`crate::Document`] are applied.
+/// Blank lines give [`DiagnosticKind::BlankLine`] and have no entry;
+/// they don't count towards the [`ParseOptions::max_entries`] and the limits of the protocol.
+#[derive(Debug, Clone)]
+pub struct TextSitemap<'a> {
+    text: &'a str,
+    options: ParseOptions,
+}
+impl<'a> TextSitemap<'a> {
+    /// Reads `text`. Lines are parsed when iterating.
+    pub fn parse(text: &'a str) -> Self {
+        Self {
+            text: text.strip_prefix('\u{feff}').unwrap_or(text),
+            options: ParseOptions::default(),
+        }
+    }
+    /// Reads `text` with `options`.
+    ///
+    /// Returns [`Error::TooLarge`] if `text` exceeds the [`ParseOptions::max_size`].
+    pub fn parse_with_options(text: &'a str, options: ParseOptions) -> Result<Self, Error> {
+        check_size(text, &options)?;
+        Ok(Self::parse(text).options(options))
+    }
+    /// Reads `bytes`, which must be UTF-8.
+    ///
+    /// Returns [`Error::UnsupportedEncoding`] for a byte order mark of UTF-16.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        from_utf8(bytes).map(Self::parse)
+    }
+    /// Set how problems in the entries are handled.
+    ///
+    /// Only the rules about the location, blank lines and the limits apply.
+    pub fn options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+    /// Returns an iterator of [`UrlEntry`].
+    ///
+    /// Uses [`log`] for logging errors in the text.
+    /// See [`TextSitemap::iterate_with_diagnostics`] to get them as data.
+    pub fn iterate(
+        &'a self,
+    ) -> Result<impl DoubleEndedIterator<Item = UrlEntry<'a>> + Clone + Debug + 'a, Error> {
+        self.iterate_with_diagnostics()
+            .map(|iter| iter.filter_map(Report::log))
+    }
+    /// Returns an iterator of a [`Report`] for every line.
+    ///
+    /// See [`crate::Document::iterate_with_diagnostics`] for how [`Error::Rejected`] is returned.
+    pub fn iterate_with_diagnostics(
+        &'a self,
+    ) -> Result<
+        impl DoubleEndedIterator<Item = Report<UrlEntry<'a>>> + ExactSizeIterator + Clone + Debug + 'a,
+        Error,
+    > {
+        check_size(self.text, &self.options)?;
+        let reports = self.reports();
+        check_fatal(&self.options, reports.clone())?;
+        Ok(reports)
+    }
+    /// Returns all the problems found in the lines.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.reports()
+            .flat_map(|report| report.diagnostics)
+            .collect()
+    }
+
+    fn reports(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Report<UrlEntry<'a>>> + ExactSizeIterator + Clone + Debug + '_
+    {
+        let options = &self.options;
+        self.lines().map(move |(index, count, end, line)| {
+            let position = TextPos::new(index as u32 + 1, 1);
+            let location = line.trim();
+            let kinds = if location.is_empty() {
+                vec![DiagnosticKind::BlankLine]
+            } else {
+                location_problems(location)
+            };
+            let diagnostics: Vec<_> = kinds
+                .into_iter()
+                .map(|kind| Diagnostic {
+                    entry: index,
+                    position,
+                    severity: options.severity(&kind),
+                    kind,
+                })
+                .collect();
+            let skipped = location.is_empty()
+                || diagnostics
+                    .iter()
+                    .any(|diagnostic| diagnostic.severity >= Severity::Error);
+            let mut report = Report {
+                index,
+                entry: Some(location).filter(|_| !skipped).map(UrlEntry::new),
+                diagnostics,
+            };
+            if !location.is_empty() {
+                check_limits(&mut report, count, || position, end as u64);
+            }
+            report
+        })
+    }
+    /// The lines, up to the [`ParseOptions::max_entries`] non-blank ones,
+    /// with their index, the number of non-blank lines before them,
+    /// and the byte offset of their end.
+    fn lines(&self) -> std::vec::IntoIter<(usize, usize, usize, &'a str)> {
+        let max_entries = self.options.max_entries.unwrap_or(usize::MAX);
+        let mut lines = Vec::new();
+        let mut count = 0;
+        let mut end = 0;
+        for (index, line) in self.text.split_inclusive('\n').enumerate() {
+            if count == max_entries {
+                break;
+            }
+            end += line.len();
+            lines.push((index, count, end, line));
+            if !line.trim().is_empty() {
+                count += 1;
+            }
+        }
+        lines.into_iter()
+    }
+}
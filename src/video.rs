@@ -0,0 +1,434 @@
+//! The [video sitemap extension](https://developers.google.com/search/docs/crawling-indexing/sitemaps/video-sitemaps).
+//!
+//! Videos are available in [`crate::UrlEntry::videos`].
+
+use crate::parse::Element;
+use crate::{DiagnosticKind, RangePolicy, W3cDatetime};
+
+/// The XML namespace of the video extension.
+pub const NAMESPACE: &str = "http://www.google.com/schemas/sitemap-video/1.1";
+
+/// Whether a [`Restriction`] or [`Platform`] lists the allowed or denied values.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Relationship {
+    Allow,
+    Deny,
+}
+impl Relationship {
+    fn parse(text: &str) -> Option<Self> {
+        if text.eq_ignore_ascii_case("allow") {
+            Some(Self::Allow)
+        } else if text.eq_ignore_ascii_case("deny") {
+            Some(Self::Deny)
+        } else {
+            None
+        }
+    }
+}
+/// The countries where the video can or can't be played.
+///
+/// `<video:restriction>`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Restriction<'a> {
+    /// The `relationship` attribute.
+    pub relationship: Relationship,
+    /// A space-separated list of [ISO 3166](https://en.wikipedia.org/wiki/ISO_3166) country codes.
+    ///
+    /// See [`Restriction::countries`].
+    pub countries: &'a str,
+}
+impl<'a> Restriction<'a> {
+    /// Iterate the country codes.
+    pub fn countries(&self) -> impl Iterator<Item = &'a str> {
+        self.countries.split_whitespace()
+    }
+}
+/// The platforms where the video can or can't be played.
+///
+/// `<video:platform>`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Platform<'a> {
+    /// The `relationship` attribute.
+    pub relationship: Relationship,
+    /// A space-separated list of `web`, `mobile`, and `tv`.
+    ///
+    /// See [`Platform::platforms`].
+    pub platforms: &'a str,
+}
+impl<'a> Platform<'a> {
+    /// Iterate the platforms.
+    pub fn platforms(&self) -> impl Iterator<Item = &'a str> {
+        self.platforms.split_whitespace()
+    }
+}
+/// The uploader of the video.
+///
+/// `<video:uploader>`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Uploader<'a> {
+    pub name: &'a str,
+    /// The URL of a page with more information about the uploader.
+    ///
+    /// The `info` attribute.
+    pub info: Option<&'a str>,
+}
+
+/// A video on the page of a [`crate::UrlEntry`].
+///
+/// `<video:video>`
+///
+/// Fields with invalid values are reported in the diagnostics and ignored,
+/// except dates, which are kept as-is (like [`crate::UrlEntry::last_modified`]).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Video<'a> {
+    /// The URL of the thumbnail.
+    ///
+    /// `<video:thumbnail_loc>`
+    pub thumbnail_location: &'a str,
+    /// `<video:title>`
+    pub title: &'a str,
+    /// `<video:description>`
+    pub description: &'a str,
+    /// The URL of the video file.
+    ///
+    /// `<video:content_loc>`
+    ///
+    /// At least one of this and [`Video::player_location`] is present.
+    pub content_location: Option<&'a str>,
+    /// The URL of a player for the video.
+    ///
+    /// `<video:player_loc>`
+    pub player_location: Option<&'a str>,
+    /// The duration in seconds, in the range `1..=28800`.
+    ///
+    /// `<video:duration>`
+    pub duration: Option<u32>,
+    /// The date after which the video is no longer available.
+    ///
+    /// `<video:expiration_date>`
+    ///
+    /// Format should be in [W3C Datetime](https://www.w3.org/TR/NOTE-datetime).
+    pub expiration_date: Option<&'a str>,
+    /// Ranges from `0.0` to `5.0`.
+    ///
+    /// `<video:rating>`
+    pub rating: Option<f32>,
+    /// `<video:view_count>`
+    pub view_count: Option<u64>,
+    /// The date the video was first published.
+    ///
+    /// `<video:publication_date>`
+    ///
+    /// Format should be in [W3C Datetime](https://www.w3.org/TR/NOTE-datetime).
+    pub publication_date: Option<&'a str>,
+    /// `<video:family_friendly>`
+    pub family_friendly: Option<bool>,
+    /// `<video:restriction>`
+    pub restriction: Option<Restriction<'a>>,
+    /// `<video:platform>`
+    pub platform: Option<Platform<'a>>,
+    /// `<video:requires_subscription>`
+    pub requires_subscription: Option<bool>,
+    /// `<video:uploader>`
+    pub uploader: Option<Uploader<'a>>,
+    /// Whether the video is a live stream.
+    ///
+    /// `<video:live>`
+    pub live: Option<bool>,
+}
+
+/// An owned version of [`Restriction`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OwnedRestriction {
+    pub relationship: Relationship,
+    pub countries: String,
+}
+/// An owned version of [`Platform`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OwnedPlatform {
+    pub relationship: Relationship,
+    pub platforms: String,
+}
+/// An owned version of [`Uploader`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OwnedUploader {
+    pub name: String,
+    pub info: Option<String>,
+}
+/// An owned version of [`Video`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct OwnedVideo {
+    pub thumbnail_location: String,
+    pub title: String,
+    pub description: String,
+    pub content_location: Option<String>,
+    pub player_location: Option<String>,
+    pub duration: Option<u32>,
+    pub expiration_date: Option<String>,
+    pub rating: Option<f32>,
+    pub view_count: Option<u64>,
+    pub publication_date: Option<String>,
+    pub family_friendly: Option<bool>,
+    pub restriction: Option<OwnedRestriction>,
+    pub platform: Option<OwnedPlatform>,
+    pub requires_subscription: Option<bool>,
+    pub uploader: Option<OwnedUploader>,
+    pub live: Option<bool>,
+}
+impl OwnedVideo {
+    /// Borrow this as a [`Video`].
+    pub fn as_video(&self) -> Video<'_> {
+        Video {
+            thumbnail_location: &self.thumbnail_location,
+            title: &self.title,
+            description: &self.description,
+            content_location: self.content_location.as_deref(),
+            player_location: self.player_location.as_deref(),
+            duration: self.duration,
+            expiration_date: self.expiration_date.as_deref(),
+            rating: self.rating,
+            view_count: self.view_count,
+            publication_date: self.publication_date.as_deref(),
+            family_friendly: self.family_friendly,
+            restriction: self.restriction.as_ref().map(|restriction| Restriction {
+                relationship: restriction.relationship,
+                countries: &restriction.countries,
+            }),
+            platform: self.platform.as_ref().map(|platform| Platform {
+                relationship: platform.relationship,
+                platforms: &platform.platforms,
+            }),
+            requires_subscription: self.requires_subscription,
+            uploader: self.uploader.as_ref().map(|uploader| Uploader {
+                name: &uploader.name,
+                info: uploader.info.as_deref(),
+            }),
+            live: self.live,
+        }
+    }
+}
+impl<'a> From<Video<'a>> for OwnedVideo {
+    fn from(video: Video<'a>) -> Self {
+        Self {
+            thumbnail_location: video.thumbnail_location.to_owned(),
+            title: video.title.to_owned(),
+            description: video.description.to_owned(),
+            content_location: video.content_location.map(str::to_owned),
+            player_location: video.player_location.map(str::to_owned),
+            duration: video.duration,
+            expiration_date: video.expiration_date.map(str::to_owned),
+            rating: video.rating,
+            view_count: video.view_count,
+            publication_date: video.publication_date.map(str::to_owned),
+            family_friendly: video.family_friendly,
+            restriction: video.restriction.map(|restriction| OwnedRestriction {
+                relationship: restriction.relationship,
+                countries: restriction.countries.to_owned(),
+            }),
+            platform: video.platform.map(|platform| OwnedPlatform {
+                relationship: platform.relationship,
+                platforms: platform.platforms.to_owned(),
+            }),
+            requires_subscription: video.requires_subscription,
+            uploader: video.uploader.map(|uploader| OwnedUploader {
+                name: uploader.name.to_owned(),
+                info: uploader.info.map(str::to_owned),
+            }),
+            live: video.live,
+        }
+    }
+}
+
+fn parse_yes_no(text: &str) -> Option<bool> {
+    if text.eq_ignore_ascii_case("yes") {
+        Some(true)
+    } else if text.eq_ignore_ascii_case("no") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Parses a `<video:video>`.
+/// Returns [`None`] if any of the required fields are missing.
+///
+/// A `<video:duration>` or `<video:rating>` out of range is handled by `range`.
+pub(crate) fn parse<'a, E: Element<'a>>(
+    node: E,
+    range: RangePolicy,
+    diagnostic: &mut impl FnMut(E, DiagnosticKind),
+) -> Option<Video<'a>> {
+    let mut thumbnail_location = None;
+    let mut title = None;
+    let mut description = None;
+    let mut video = Video {
+        thumbnail_location: "",
+        title: "",
+        description: "",
+        content_location: None,
+        player_location: None,
+        duration: None,
+        expiration_date: None,
+        rating: None,
+        view_count: None,
+        publication_date: None,
+        family_friendly: None,
+        restriction: None,
+        platform: None,
+        requires_subscription: None,
+        uploader: None,
+        live: None,
+    };
+    let mut seen = Vec::new();
+    for child in node.children() {
+        if child.namespace() != Some(NAMESPACE) {
+            continue;
+        }
+        let text = if let Some(text) = child.text() {
+            text.trim()
+        } else {
+            continue;
+        };
+        let name = child.name();
+        let field = || format!("video:{name}");
+        let mut invalid = |child| {
+            diagnostic(
+                child,
+                DiagnosticKind::InvalidExtensionField {
+                    field: field(),
+                    value: text.to_owned(),
+                },
+            )
+        };
+        match name {
+            "thumbnail_loc" => thumbnail_location = Some(text),
+            "title" => title = Some(text),
+            "description" => description = Some(text),
+            "content_loc" => video.content_location = Some(text),
+            "player_loc" => video.player_location = Some(text),
+            "duration" => match text.parse() {
+                Ok(duration) if (1..=28800).contains(&duration) => video.duration = Some(duration),
+                Ok(duration) => {
+                    diagnostic(
+                        child,
+                        DiagnosticKind::ExtensionFieldOutOfRange {
+                            field: field(),
+                            value: text.to_owned(),
+                        },
+                    );
+                    if range == RangePolicy::Clamp {
+                        video.duration = Some(u32::clamp(duration, 1, 28800));
+                    }
+                }
+                Err(_) => invalid(child),
+            },
+            "expiration_date" | "publication_date" => {
+                if W3cDatetime::parse(text).is_err() {
+                    invalid(child);
+                }
+                if name == "expiration_date" {
+                    video.expiration_date = Some(text);
+                } else {
+                    video.publication_date = Some(text);
+                }
+            }
+            "rating" => match text.parse() {
+                Ok(rating) if (0.0..=5.0).contains(&rating) => video.rating = Some(rating),
+                Ok(rating) => {
+                    diagnostic(
+                        child,
+                        DiagnosticKind::ExtensionFieldOutOfRange {
+                            field: field(),
+                            value: text.to_owned(),
+                        },
+                    );
+                    if range == RangePolicy::Clamp && !f32::is_nan(rating) {
+                        video.rating = Some(rating.clamp(0.0, 5.0));
+                    }
+                }
+                Err(_) => invalid(child),
+            },
+            "view_count" => match text.parse() {
+                Ok(view_count) => video.view_count = Some(view_count),
+                Err(_) => invalid(child),
+            },
+            "family_friendly" | "requires_subscription" | "live" => match parse_yes_no(text) {
+                Some(value) => match name {
+                    "family_friendly" => video.family_friendly = Some(value),
+                    "requires_subscription" => video.requires_subscription = Some(value),
+                    _ => video.live = Some(value),
+                },
+                None => invalid(child),
+            },
+            "restriction" | "platform" => {
+                match child
+                    .attribute("relationship")
+                    .and_then(Relationship::parse)
+                {
+                    Some(relationship) if name == "restriction" => {
+                        video.restriction = Some(Restriction {
+                            relationship,
+                            countries: text,
+                        })
+                    }
+                    Some(relationship) => {
+                        video.platform = Some(Platform {
+                            relationship,
+                            platforms: text,
+                        })
+                    }
+                    None => invalid(child),
+                }
+            }
+            "uploader" => {
+                video.uploader = Some(Uploader {
+                    name: text,
+                    info: child.attribute("info"),
+                })
+            }
+            _ => continue,
+        }
+        if seen.contains(&name) {
+            diagnostic(child, DiagnosticKind::DuplicateExtensionField(field()));
+        } else {
+            seen.push(name);
+        }
+    }
+    let mut missing = |field: &str| {
+        diagnostic(
+            node,
+            DiagnosticKind::MissingExtensionField {
+                element: "video:video".to_owned(),
+                field: field.to_owned(),
+            },
+        )
+    };
+    if thumbnail_location.is_none() {
+        missing("video:thumbnail_loc");
+    }
+    if title.is_none() {
+        missing("video:title");
+    }
+    if description.is_none() {
+        missing("video:description");
+    }
+    if video.content_location.is_none() && video.player_location.is_none() {
+        diagnostic(
+            node,
+            DiagnosticKind::MissingOneOfExtensionFields {
+                element: "video:video".to_owned(),
+                fields: vec![
+                    "video:content_loc".to_owned(),
+                    "video:player_loc".to_owned(),
+                ],
+            },
+        );
+    }
+    video.thumbnail_location = thumbnail_location?;
+    video.title = title?;
+    video.description = description?;
+    if video.content_location.is_none() && video.player_location.is_none() {
+        return None;
+    }
+    Some(video)
+}
@@ -0,0 +1,476 @@
+//! Writing of sitemaps.
+//!
+//! The output follows [the spec](https://sitemaps.org/protocol.html),
+//! with all text properly escaped.
+
+use crate::{SitemapEntry, UrlEntry, NAMESPACE};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+/// Escapes the XML entities in `text`.
+pub fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(|c| matches!(c, '&' | '<' | '>' | '"' | '\'')) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len() + 16);
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// The maximum number of entries in a sitemap, according to the spec.
+pub const MAX_ENTRIES: usize = 50_000;
+/// The maximum size of a uncompressed sitemap, according to the spec.
+pub const MAX_SIZE: u64 = 50 * 1024 * 1024;
+
+const URLSET_END: &[u8] = b"</urlset>\n";
+const SITEMAPINDEX_END: &[u8] = b"</sitemapindex>\n";
+
+/// Writes a `<urlset>` to any [`Write`].
+///
+/// Call [`SitemapWriter::write_entry`] for every entry, then [`SitemapWriter::finish`].
+/// If [`SitemapWriter::finish`] isn't called, the document will be incomplete.
+///
+/// See [`SplittingWriter`] to respect the limits of the spec.
+///
+/// Only the fields of the sitemap protocol are written;
+/// extensions (e.g. [`UrlEntry::images`]) are not.
+#[derive(Debug)]
+pub struct SitemapWriter<W: Write> {
+    writer: W,
+    pretty: bool,
+    started: bool,
+    entries: usize,
+    bytes: u64,
+}
+impl<W: Write> SitemapWriter<W> {
+    /// Creates a new writer. Nothing is written until the first entry.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            pretty: false,
+            started: false,
+            entries: 0,
+            bytes: 0,
+        }
+    }
+    /// Put every element on it's own line, indented.
+    ///
+    /// Defaults to `false`.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+    /// Writes `entry` as a `<url>`.
+    ///
+    /// Returns an error with [`io::ErrorKind::InvalidInput`], without writing anything,
+    /// if the [`UrlEntry::priority`] is NaN or outside `0.0..=1.0`.
+    pub fn write_entry(&mut self, entry: &UrlEntry<'_>) -> io::Result<()> {
+        check_entry(entry)?;
+        let mut buf = Vec::new();
+        serialize_entry(entry, self.pretty, &mut buf);
+        self.write_serialized(&buf)
+    }
+    /// The number of entries written.
+    pub fn entries_written(&self) -> usize {
+        self.entries
+    }
+    /// The number of bytes written, excluding the closing tag written by [`SitemapWriter::finish`].
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes
+    }
+    /// Closes the `<urlset>` and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.start()?;
+        self.writer.write_all(URLSET_END)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn start(&mut self) -> io::Result<()> {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
+        let header = start_document("urlset", self.pretty);
+        self.bytes += header.len() as u64;
+        self.writer.write_all(header.as_bytes())
+    }
+    fn write_serialized(&mut self, entry: &[u8]) -> io::Result<()> {
+        self.start()?;
+        self.writer.write_all(entry)?;
+        self.entries += 1;
+        self.bytes += entry.len() as u64;
+        Ok(())
+    }
+}
+
+/// Writes a `<sitemapindex>` to any [`Write`].
+///
+/// Call [`SitemapIndexWriter::write_entry`] for every entry, then [`SitemapIndexWriter::finish`].
+/// If [`SitemapIndexWriter::finish`] isn't called, the document will be incomplete.
+#[derive(Debug)]
+pub struct SitemapIndexWriter<W: Write> {
+    writer: W,
+    pretty: bool,
+    started: bool,
+}
+impl<W: Write> SitemapIndexWriter<W> {
+    /// Creates a new writer. Nothing is written until the first entry.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            pretty: false,
+            started: false,
+        }
+    }
+    /// Put every element on it's own line, indented.
+    ///
+    /// Defaults to `false`.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+    /// Writes `entry` as a `<sitemap>`.
+    pub fn write_entry(&mut self, entry: &SitemapEntry<'_>) -> io::Result<()> {
+        self.start()?;
+        let (indent, newline) = whitespace(self.pretty);
+        let mut buf = format!("{indent}<sitemap>{newline}").into_bytes();
+        write_element(&mut buf, self.pretty, "loc", entry.location);
+        if let Some(last_modified) = entry.last_modified {
+            write_element(&mut buf, self.pretty, "lastmod", last_modified);
+        }
+        buf.extend_from_slice(format!("{indent}</sitemap>{newline}").as_bytes());
+        self.writer.write_all(&buf)
+    }
+    /// Closes the `<sitemapindex>` and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.start()?;
+        self.writer.write_all(SITEMAPINDEX_END)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn start(&mut self) -> io::Result<()> {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
+        self.writer
+            .write_all(start_document("sitemapindex", self.pretty).as_bytes())
+    }
+}
+
+/// Checks that `entry` can be written as-is.
+fn check_entry(entry: &UrlEntry<'_>) -> io::Result<()> {
+    match entry.priority {
+        Some(priority) if !(0.0..=1.0).contains(&priority) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("priority {priority} is out of range"),
+        )),
+        _ => Ok(()),
+    }
+}
+/// Writes `entry` as a `<url>` to `buf`.
+fn serialize_entry(entry: &UrlEntry<'_>, pretty: bool, buf: &mut Vec<u8>) {
+    let (indent, newline) = whitespace(pretty);
+    buf.extend_from_slice(format!("{indent}<url>{newline}").as_bytes());
+    write_element(buf, pretty, "loc", entry.location);
+    if let Some(last_modified) = entry.last_modified {
+        write_element(buf, pretty, "lastmod", last_modified);
+    }
+    if let Some(frequency) = entry.change_frequency {
+        write_element(buf, pretty, "changefreq", frequency.as_str());
+    }
+    if let Some(priority) = entry.priority {
+        write_element(buf, pretty, "priority", &priority.to_string());
+    }
+    buf.extend_from_slice(format!("{indent}</url>{newline}").as_bytes());
+}
+fn start_document(root: &str, pretty: bool) -> String {
+    let (_, newline) = whitespace(pretty);
+    format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<{root} xmlns=\"{NAMESPACE}\">{newline}")
+}
+/// Writes a child of a entry.
+fn write_element(buf: &mut Vec<u8>, pretty: bool, name: &str, text: &str) {
+    let (indent, newline) = whitespace(pretty);
+    buf.extend_from_slice(
+        format!("{indent}{indent}<{name}>{}</{name}>{newline}", escape(text)).as_bytes(),
+    );
+}
+/// The indentation and newline to use.
+fn whitespace(pretty: bool) -> (&'static str, &'static str) {
+    if pretty {
+        ("  ", "\n")
+    } else {
+        ("", "")
+    }
+}
+
+/// Where the files of a [`SplittingWriter`] are written.
+pub trait Sink {
+    type Writer: Write;
+
+    /// Create the file named `name`.
+    fn create(&mut self, name: &str) -> io::Result<Self::Writer>;
+    /// Called when the file named `name` is completely written.
+    ///
+    /// By default, this does nothing.
+    fn finish(&mut self, name: &str, writer: Self::Writer) -> io::Result<()> {
+        let _ = (name, writer);
+        Ok(())
+    }
+}
+/// A [`Sink`] writing the files to a directory.
+#[derive(Debug, Clone)]
+pub struct DirectorySink {
+    path: PathBuf,
+}
+impl DirectorySink {
+    /// The directory at `path` must exist.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+impl Sink for DirectorySink {
+    type Writer = BufWriter<File>;
+
+    fn create(&mut self, name: &str) -> io::Result<Self::Writer> {
+        File::create(self.path.join(name)).map(BufWriter::new)
+    }
+    fn finish(&mut self, _name: &str, mut writer: Self::Writer) -> io::Result<()> {
+        writer.flush()
+    }
+}
+/// A [`Sink`] keeping the files in memory.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    /// The files, by name.
+    pub files: BTreeMap<String, Vec<u8>>,
+}
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Sink for MemorySink {
+    type Writer = Vec<u8>;
+
+    fn create(&mut self, _name: &str) -> io::Result<Self::Writer> {
+        Ok(Vec::new())
+    }
+    fn finish(&mut self, name: &str, writer: Self::Writer) -> io::Result<()> {
+        self.files.insert(name.to_owned(), writer);
+        Ok(())
+    }
+}
+
+/// The sitemap index of a [`SplittingWriter`] would exceed the limits of the spec.
+///
+/// Returned as the inner error of a [`io::Error`] with [`io::ErrorKind::InvalidInput`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IndexTooLarge {
+    /// More than [`MAX_ENTRIES`] sitemaps. Contains the number of sitemaps.
+    Sitemaps(usize),
+    /// More than [`MAX_SIZE`] bytes. Contains the size of the index.
+    Size(u64),
+}
+impl Display for IndexTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sitemaps(sitemaps) => write!(
+                f,
+                "sitemap index would have {sitemaps} sitemaps, more than {MAX_ENTRIES}"
+            ),
+            Self::Size(size) => write!(
+                f,
+                "sitemap index would be {size} bytes, more than {MAX_SIZE}"
+            ),
+        }
+    }
+}
+impl std::error::Error for IndexTooLarge {}
+impl From<IndexTooLarge> for io::Error {
+    fn from(err: IndexTooLarge) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, err)
+    }
+}
+
+/// Writes any number of entries to multiple sitemaps, and a sitemap index referencing them.
+///
+/// A new sitemap is started when either [`MAX_ENTRIES`] or [`MAX_SIZE`]
+/// (or the values set by [`SplittingWriter::max_entries`] and [`SplittingWriter::max_size`])
+/// would be exceeded.
+///
+/// The sitemaps are named `sitemap-1.xml`, `sitemap-2.xml`, and so on,
+/// and the index `sitemap_index.xml`. The prefix can be changed using [`SplittingWriter::prefix`].
+///
+/// The index itself is also limited to [`MAX_ENTRIES`] sitemaps and [`MAX_SIZE`] bytes;
+/// exceeding them returns an error containing [`IndexTooLarge`].
+#[derive(Debug)]
+pub struct SplittingWriter<S: Sink> {
+    sink: S,
+    base_url: String,
+    prefix: String,
+    pretty: bool,
+    max_entries: usize,
+    max_size: u64,
+    current: Option<SitemapWriter<S::Writer>>,
+    parts: Vec<String>,
+}
+impl<S: Sink> SplittingWriter<S> {
+    /// Creates a new writer, writing the files to `sink`.
+    ///
+    /// `base_url` is the URL the files will be available under, e.g. `https://example.com/`.
+    /// It's used for the `<loc>` of the sitemaps in the index.
+    pub fn new(sink: S, base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        Self {
+            sink,
+            base_url,
+            prefix: "sitemap".to_owned(),
+            pretty: false,
+            max_entries: MAX_ENTRIES,
+            max_size: MAX_SIZE,
+            current: None,
+            parts: Vec::new(),
+        }
+    }
+    /// Set the prefix of the names of the files.
+    ///
+    /// Defaults to `sitemap`.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+    /// Put every element on it's own line, indented.
+    ///
+    /// Defaults to `false`.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+    /// Set the maximum number of entries in each sitemap.
+    ///
+    /// Defaults to [`MAX_ENTRIES`], and is capped to it.
+    ///
+    /// # Panics
+    ///
+    /// If `max_entries` is `0`.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        assert!(max_entries > 0, "max_entries must be at least 1");
+        self.max_entries = max_entries.min(MAX_ENTRIES);
+        self
+    }
+    /// Set the maximum size in bytes of each sitemap.
+    ///
+    /// Defaults to [`MAX_SIZE`], and is capped to it.
+    /// Entries which don't fit in an empty sitemap are rejected by [`SplittingWriter::write_entry`].
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size.min(MAX_SIZE);
+        self
+    }
+    /// Writes `entry` to the current sitemap, or a new one if the limits are reached.
+    ///
+    /// Returns an error containing [`IndexTooLarge::Sitemaps`]
+    /// if a new sitemap is needed, but the index is full.
+    /// Returns an error with [`io::ErrorKind::InvalidInput`] if the `entry` alone
+    /// exceeds [`SplittingWriter::max_size`], and the same errors as [`SitemapWriter::write_entry`].
+    pub fn write_entry(&mut self, entry: &UrlEntry<'_>) -> io::Result<()> {
+        check_entry(entry)?;
+        let mut buf = Vec::new();
+        serialize_entry(entry, self.pretty, &mut buf);
+        let end = buf.len() + URLSET_END.len();
+        let empty = (start_document("urlset", self.pretty).len() + end) as u64;
+        if empty > self.max_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "entry would make a sitemap of {empty} bytes, more than {}",
+                    self.max_size
+                ),
+            ));
+        }
+        let full = self.current.as_ref().map_or(true, |current| {
+            current.entries_written() >= self.max_entries
+                || current.bytes_written() + end as u64 > self.max_size
+        });
+        if full {
+            if self.parts.len() >= MAX_ENTRIES {
+                return Err(IndexTooLarge::Sitemaps(self.parts.len() + 1).into());
+            }
+            self.finish_part()?;
+            let name = format!("{}-{}.xml", self.prefix, self.parts.len() + 1);
+            let writer = self.sink.create(&name)?;
+            self.parts.push(name);
+            self.current = Some(SitemapWriter::new(writer).pretty(self.pretty));
+        }
+        // `current` is always set above.
+        if let Some(current) = &mut self.current {
+            current.write_serialized(&buf)?;
+        }
+        Ok(())
+    }
+    /// The names of the sitemaps written so far.
+    pub fn parts(&self) -> &[String] {
+        &self.parts
+    }
+    /// Finishes the current sitemap, writes the index, and returns the sink.
+    ///
+    /// If no entries were written, a single empty sitemap is written.
+    ///
+    /// Returns an error containing [`IndexTooLarge::Size`], without writing the index,
+    /// if it would exceed [`MAX_SIZE`] (e.g. because of a long `base_url`).
+    pub fn finish(mut self) -> io::Result<S> {
+        if self.parts.is_empty() {
+            let name = format!("{}-1.xml", self.prefix);
+            let writer = self.sink.create(&name)?;
+            self.parts.push(name);
+            self.current = Some(SitemapWriter::new(writer).pretty(self.pretty));
+        }
+        self.finish_part()?;
+        // Write to memory first, to check the size before creating the file.
+        let mut index = SitemapIndexWriter::new(Vec::new()).pretty(self.pretty);
+        for part in &self.parts {
+            index.write_entry(&SitemapEntry {
+                location: &format!("{}{part}", self.base_url),
+                last_modified: None,
+            })?;
+        }
+        let index = index.finish()?;
+        if index.len() as u64 > MAX_SIZE {
+            return Err(IndexTooLarge::Size(index.len() as u64).into());
+        }
+        let name = format!("{}_index.xml", self.prefix);
+        let mut writer = self.sink.create(&name)?;
+        writer.write_all(&index)?;
+        writer.flush()?;
+        self.sink.finish(&name, writer)?;
+        Ok(self.sink)
+    }
+
+    fn finish_part(&mut self) -> io::Result<()> {
+        if let Some(current) = self.current.take() {
+            let writer = current.finish()?;
+            // `parts` is pushed to when `current` is set.
+            let name = self.parts.last().map_or("", String::as_str);
+            self.sink.finish(name, writer)?;
+        }
+        Ok(())
+    }
+}
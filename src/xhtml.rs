@@ -0,0 +1,91 @@
+//! Language alternates, declared with [`<xhtml:link rel="alternate">`](https://developers.google.com/search/docs/specialty/international/localized-versions#sitemap).
+//!
+//! Alternates are available in [`crate::UrlEntry::alternates`]. Other `<xhtml:link>`,
+//! and those missing `hreflang` or `href`, are kept in [`crate::UrlEntry::extensions`].
+
+use crate::parse::Element;
+use crate::DiagnosticKind;
+
+/// The XML namespace of XHTML.
+pub const NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+/// The [`Alternate::hreflang`] of the page for unmatched languages.
+pub const X_DEFAULT: &str = "x-default";
+
+/// A version of the page of a [`crate::UrlEntry`] in another language or region.
+///
+/// `<xhtml:link rel="alternate" hreflang="..." href="..."/>`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Alternate<'a> {
+    /// The language (and optionally region) of the alternate, or [`X_DEFAULT`].
+    ///
+    /// The `hreflang` attribute.
+    pub hreflang: &'a str,
+    /// The URL of the alternate.
+    ///
+    /// The `href` attribute.
+    pub href: &'a str,
+}
+impl<'a> Alternate<'a> {
+    /// Check if this is the `x-default` alternate.
+    pub fn is_default(&self) -> bool {
+        self.hreflang.eq_ignore_ascii_case(X_DEFAULT)
+    }
+}
+/// An owned version of [`Alternate`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OwnedAlternate {
+    /// See [`Alternate::hreflang`].
+    pub hreflang: String,
+    /// See [`Alternate::href`].
+    pub href: String,
+}
+impl OwnedAlternate {
+    /// Borrow this as a [`Alternate`].
+    pub fn as_alternate(&self) -> Alternate<'_> {
+        Alternate {
+            hreflang: &self.hreflang,
+            href: &self.href,
+        }
+    }
+}
+impl<'a> From<Alternate<'a>> for OwnedAlternate {
+    fn from(alternate: Alternate<'a>) -> Self {
+        Self {
+            hreflang: alternate.hreflang.to_owned(),
+            href: alternate.href.to_owned(),
+        }
+    }
+}
+
+/// Parses a `<xhtml:link>`.
+/// Returns [`None`] if it isn't `rel="alternate"` or is missing `hreflang` or `href`.
+pub(crate) fn parse<'a, E: Element<'a>>(
+    node: E,
+    diagnostic: &mut impl FnMut(E, DiagnosticKind),
+) -> Option<Alternate<'a>> {
+    if !node
+        .attribute("rel")
+        .map_or(false, |rel| rel.trim().eq_ignore_ascii_case("alternate"))
+    {
+        return None;
+    }
+    let mut attribute = |name: &str| {
+        let value = node.attribute(name).map(str::trim);
+        if value.is_none() {
+            diagnostic(
+                node,
+                DiagnosticKind::MissingExtensionField {
+                    element: "xhtml:link".to_owned(),
+                    field: name.to_owned(),
+                },
+            );
+        }
+        value
+    };
+    let hreflang = attribute("hreflang");
+    let href = attribute("href");
+    Some(Alternate {
+        hreflang: hreflang?,
+        href: href?,
+    })
+}
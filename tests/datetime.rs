@@ -0,0 +1,170 @@
+use sitemap_iter::{DatetimeParseError, Precision, W3cDatetime};
+
+fn parse(text: &str) -> W3cDatetime {
+    W3cDatetime::parse(text).unwrap_or_else(|err| panic!("{text:?}: {err:?}"))
+}
+
+#[test]
+fn formats() {
+    let cases = [
+        ("1997", Precision::Year),
+        ("1997-07", Precision::Month),
+        ("1997-07-16", Precision::Day),
+        ("1997-07-16T19:20+01:00", Precision::Minute),
+        ("1997-07-16T19:20:30+01:00", Precision::Second),
+        ("1997-07-16T19:20:30.45+01:00", Precision::Fraction),
+        ("1997-07-16T19:20:30.45Z", Precision::Fraction),
+        ("1997-07-16T19:20:30-05:30", Precision::Second),
+    ];
+    for (text, precision) in cases {
+        let datetime = parse(text);
+        assert_eq!(datetime.precision(), precision, "{text}");
+        assert_eq!(datetime.to_string(), text);
+    }
+}
+#[test]
+fn components() {
+    let datetime = parse("1997-07-16T19:20:30.45-05:30");
+    assert_eq!(datetime.year(), 1997);
+    assert_eq!(datetime.month(), 7);
+    assert_eq!(datetime.day(), 16);
+    assert_eq!(datetime.hour(), 19);
+    assert_eq!(datetime.minute(), 20);
+    assert_eq!(datetime.second(), 30);
+    assert_eq!(datetime.nanosecond(), 450_000_000);
+    assert_eq!(datetime.offset_minutes(), Some(-330));
+
+    let date = parse("2004-02");
+    assert_eq!((date.month(), date.day(), date.hour()), (2, 1, 0));
+    assert_eq!(date.offset_minutes(), None);
+}
+#[test]
+fn fraction() {
+    let datetime = parse("2004-01-01T00:00:00.123456789123Z");
+    assert_eq!(datetime.nanosecond(), 123_456_789);
+    assert_eq!(datetime.to_string(), "2004-01-01T00:00:00.123456789Z");
+    assert_eq!(parse("2004-01-01T00:00:00.5Z").nanosecond(), 500_000_000);
+}
+#[test]
+fn invalid_format() {
+    for text in [
+        "",
+        "97",
+        "1997-7",
+        "1997-07-16T19:20",
+        "1997-07-16T19:20:30",
+        "1997-07-16 19:20Z",
+        "1997-07-16T19Z",
+        "1997-07-16T19:20:30.Z",
+        "1997-07-16T19:20+0100",
+        "1997-07-16T19:20+01:00 ",
+        "1997-07-16Z",
+        "1997/07/16",
+        "yesterday",
+        "１９９７",
+    ] {
+        assert_eq!(
+            W3cDatetime::parse(text),
+            Err(DatetimeParseError::InvalidFormat),
+            "{text:?}"
+        );
+    }
+}
+#[test]
+fn out_of_range() {
+    for text in [
+        "1997-00",
+        "1997-13",
+        "1997-04-31",
+        "1997-02-29",
+        "1900-02-29",
+        "1997-07-16T24:00Z",
+        "1997-07-16T19:60Z",
+        "1997-07-16T19:20:60Z",
+        "1997-07-16T19:20+24:00",
+    ] {
+        assert_eq!(
+            W3cDatetime::parse(text),
+            Err(DatetimeParseError::OutOfRange),
+            "{text:?}"
+        );
+    }
+    parse("2000-02-29");
+    parse("2004-02-29");
+}
+#[test]
+fn unix_timestamp() {
+    assert_eq!(parse("1970").unix_timestamp(), 0);
+    assert_eq!(parse("2000-03-01").unix_timestamp(), 951_868_800);
+    assert_eq!(
+        parse("2000-03-01T01:00+01:00").unix_timestamp(),
+        951_868_800
+    );
+    assert_eq!(parse("1969-12-31T23:59:59Z").unix_timestamp(), -1);
+}
+#[test]
+fn ordering() {
+    assert_eq!(parse("2004-01-01T10:00+01:00"), parse("2004-01-01T09:00Z"));
+    assert!(parse("2004-01-01T10:00:00Z") > parse("2004-01-01T10:00Z"));
+    assert!(parse("2004-01-01") < parse("2004-01-01T00:00Z"));
+    assert!(parse("2004-01-01T00:00:00.1Z") > parse("2004-01-01T00:00:00Z"));
+    assert!(parse("2003-12-31T23:00-02:00") > parse("2004-01-01T00:00Z"));
+}
+#[test]
+fn rfc822() {
+    let cases = [
+        ("Sat, 07 Sep 2002 09:42:31 GMT", "2002-09-07T09:42:31Z"),
+        ("7 Sep 2002 09:42:31 +0200", "2002-09-07T09:42:31+02:00"),
+        ("Mon, 01 jan 01 00:00 EST", "2001-01-01T00:00-05:00"),
+        ("31 Dec 99 23:59:59 PDT", "1999-12-31T23:59:59-07:00"),
+        ("29 Feb 2004 12:00:00 -0330", "2004-02-29T12:00:00-03:30"),
+    ];
+    for (text, expected) in cases {
+        let datetime = W3cDatetime::parse_rfc822(text).unwrap();
+        assert_eq!(datetime.to_string(), expected, "{text}");
+    }
+    assert_eq!(
+        W3cDatetime::parse_rfc822("Mon, 01 Jan 2001 00:00"),
+        Err(DatetimeParseError::InvalidFormat)
+    );
+    for text in [
+        "",
+        "2002-09-07",
+        "007 Sep 2002 09:42:31 GMT",
+        "07 September 2002 09:42:31 GMT",
+        "07 Sep 202 09:42:31 GMT",
+        "Sat, 07 Sep 99999999999 09:42:31 GMT",
+        "07 Sep 2002 9:42 GMT",
+        "07 Sep 2002 09:42:31 CET",
+        "07 Sep 2002 09:42:31 GMT extra",
+    ] {
+        assert_eq!(
+            W3cDatetime::parse_rfc822(text),
+            Err(DatetimeParseError::InvalidFormat),
+            "{text:?}"
+        );
+    }
+    assert_eq!(
+        W3cDatetime::parse_rfc822("31 Feb 2002 09:42:31 GMT"),
+        Err(DatetimeParseError::OutOfRange)
+    );
+}
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono() {
+    use std::convert::TryFrom;
+    let datetime = parse("1997-07-16T19:20:30.45+01:00");
+    let chrono = chrono::DateTime::<chrono::FixedOffset>::from(datetime);
+    assert_eq!(chrono.timestamp(), datetime.unix_timestamp());
+    assert_eq!(W3cDatetime::try_from(chrono).unwrap(), datetime);
+}
+#[cfg(feature = "time")]
+#[test]
+fn time() {
+    use std::convert::TryFrom;
+    let datetime = parse("1997-07-16T19:20:30.45-01:00");
+    let time = time::OffsetDateTime::from(datetime);
+    assert_eq!(time.unix_timestamp(), datetime.unix_timestamp());
+    assert_eq!(time.offset().whole_minutes(), -60);
+    assert_eq!(W3cDatetime::try_from(time).unwrap(), datetime);
+}
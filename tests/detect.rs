@@ -0,0 +1,110 @@
+use sitemap_iter::detect::{detect, Format, Sitemap};
+use sitemap_iter::Error;
+
+#[test]
+fn formats() {
+    let cases: [(&[u8], Format); 14] = [
+        (
+            br#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"/>"#,
+            Format::Urlset,
+        ),
+        (b"\xef\xbb\xbf <sitemapindex/>", Format::SitemapIndex),
+        (b"<rss version=\"2.0\"><channel/></rss>", Format::Rss),
+        (
+            br#"<feed xmlns="http://www.w3.org/2005/Atom"/>"#,
+            Format::Atom,
+        ),
+        (b"\x1f\x8b\x08\x00", Format::Gzip),
+        (b"<!DOCTYPE html><html></html>", Format::Html),
+        (b"<HTML><body>Not Found</body></HTML>", Format::Html),
+        (b"<head><title>404</title></head>", Format::Html),
+        (b"<!-- error --><body><p>Gone</p></body>", Format::Html),
+        (b"<catalog/>", Format::Unknown),
+        (
+            b"https://example.com/a\nhttps://example.com/b",
+            Format::Text,
+        ),
+        (b"\n\n  HTTP://example.com/  \r\nfoo", Format::Text),
+        (b"", Format::Empty),
+        (b" \r\n\t", Format::Empty),
+    ];
+    for (bytes, format) in cases {
+        assert_eq!(
+            detect(bytes),
+            format,
+            "{:?}",
+            String::from_utf8_lossy(bytes)
+        );
+    }
+}
+#[test]
+fn unrecognized_text() {
+    for text in [
+        "Not Found",
+        r#"{"error": "not found"}"#,
+        "example.com/sitemap.xml",
+        "ftp://example.com/a",
+        "https://",
+        "https:///path",
+        "https://example.com/a b",
+    ] {
+        assert_eq!(detect(text.as_bytes()), Format::Unknown, "{text:?}");
+        assert_eq!(
+            Sitemap::parse(text.as_bytes()).err(),
+            Some(Error::UnknownFormat),
+            "{text:?}"
+        );
+    }
+}
+#[test]
+fn errors() {
+    assert_eq!(Sitemap::parse(b"").err(), Some(Error::Empty));
+    assert_eq!(Sitemap::parse(b"\n ").err(), Some(Error::Empty));
+    assert_eq!(Sitemap::parse(b"<head></head>").err(), Some(Error::Html));
+    assert_eq!(
+        Sitemap::parse(b"<catalog/>").err(),
+        Some(Error::UnknownFormat)
+    );
+    assert_eq!(Sitemap::parse(b"\x1f\x8b\x08").err(), Some(Error::Gzipped));
+    let text = Sitemap::parse(b"https://example.com/a\n").unwrap();
+    assert_eq!(text.format(), Format::Text);
+}
+#[cfg(feature = "gzip")]
+#[test]
+fn gzipped() {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use sitemap_iter::ParseOptions;
+    use std::io::Write;
+
+    let xml = br#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+        <url><loc>https://example.com/</loc></url>
+    </urlset>"#;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(xml).unwrap();
+    let gzipped = encoder.finish().unwrap();
+
+    let mut buffer = Vec::new();
+    let sitemap =
+        Sitemap::parse_with_buffer(&gzipped, &mut buffer, ParseOptions::default()).unwrap();
+    assert_eq!(sitemap.format(), Format::Urlset);
+
+    let mut buffer = Vec::new();
+    let options = ParseOptions::default().max_size(10);
+    assert_eq!(
+        Sitemap::parse_with_buffer(&gzipped, &mut buffer, options).err(),
+        Some(Error::TooLarge(10))
+    );
+
+    let mut buffer = Vec::new();
+    assert!(matches!(
+        Sitemap::parse_with_buffer(&gzipped[..20], &mut buffer, ParseOptions::default()),
+        Err(Error::InvalidGzip(_))
+    ));
+
+    // Plain input is parsed as-is.
+    let mut buffer = Vec::new();
+    let sitemap = Sitemap::parse_with_buffer(xml, &mut buffer, ParseOptions::default()).unwrap();
+    assert_eq!(sitemap.format(), Format::Urlset);
+    assert!(buffer.is_empty());
+}
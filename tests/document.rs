@@ -0,0 +1,72 @@
+use sitemap_iter::{DiagnosticKind, Document, Error, ParseOptions, TextPos};
+
+#[test]
+fn positions() {
+    let xml = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\r\n\
+        <url><loc>https://example.com/ä</loc><lastmod>gestern</lastmod></url>\r\n\
+        <!-- ä -->  <url><loc>https://example.com/</loc><priority>2</priority></url>\n\
+        </urlset>";
+    let positions: Vec<_> = Document::parse(xml)
+        .unwrap()
+        .diagnostics()
+        .unwrap()
+        .into_iter()
+        .filter(|diagnostic| {
+            !matches!(
+                diagnostic.kind,
+                DiagnosticKind::LocationNotPercentEncoded(_)
+            )
+        })
+        .map(|diagnostic| diagnostic.position)
+        .collect();
+    assert_eq!(positions, [TextPos::new(2, 38), TextPos::new(3, 49)]);
+}
+#[test]
+fn too_many_entries() {
+    let urls = "<url><loc>https://example.com/</loc></url>\n".repeat(50_002);
+    let xml =
+        format!("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n{urls}</urlset>");
+    let diagnostics = Document::parse(&xml).unwrap().diagnostics().unwrap();
+    let positions: Vec<_> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.kind == DiagnosticKind::TooManyEntries)
+        .map(|diagnostic| (diagnostic.entry, diagnostic.position))
+        .collect();
+    assert_eq!(
+        positions,
+        [
+            (50_000, TextPos::new(50_002, 1)),
+            (50_001, TextPos::new(50_003, 1))
+        ]
+    );
+}
+#[test]
+fn diagnostics() {
+    let index = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+        <sitemap><lastmod>2023</lastmod></sitemap>
+    </sitemapindex>"#;
+    let kinds: Vec<_> = Document::parse(index)
+        .unwrap()
+        .diagnostics()
+        .unwrap()
+        .into_iter()
+        .map(|diagnostic| diagnostic.kind)
+        .collect();
+    assert_eq!(kinds, [DiagnosticKind::MissingLocation]);
+
+    let valid = r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+        <url><loc>https://example.com/</loc></url>
+    </urlset>"#;
+    assert_eq!(
+        Document::parse(valid).unwrap().diagnostics(),
+        Ok(Vec::new())
+    );
+    let doc = Document::parse(valid)
+        .unwrap()
+        .options(ParseOptions::default().max_size(10));
+    assert_eq!(doc.diagnostics(), Err(Error::TooLarge(10)));
+    assert_eq!(
+        Document::parse("<catalog/>").unwrap().diagnostics(),
+        Err(Error::UrlsetMissing)
+    );
+}
@@ -0,0 +1,83 @@
+use sitemap_iter::detect::Sitemap;
+use sitemap_iter::feed::Feed;
+use sitemap_iter::{Document, Error, OwnedDocument, TextSitemap};
+
+const LATIN_1: &[u8] = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n\
+    <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\
+    <url><loc>https://example.com/caf\xe9</loc></url></urlset>";
+/// A UTF-8 body with a declaration of ISO-8859-1.
+const MISDECLARED: &str = "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n\
+    <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\
+    <url><loc>https://example.com/café</loc></url></urlset>";
+
+fn unsupported(label: &str) -> Option<Error> {
+    Some(Error::UnsupportedEncoding(label.to_owned()))
+}
+
+#[test]
+fn declared_encoding() {
+    assert_eq!(
+        Document::from_bytes(LATIN_1).err(),
+        unsupported("ISO-8859-1")
+    );
+    assert_eq!(
+        Document::from_bytes(MISDECLARED.as_bytes()).err(),
+        unsupported("ISO-8859-1")
+    );
+    assert_eq!(Sitemap::parse(LATIN_1).err(), unsupported("ISO-8859-1"));
+    let rss = b"<?xml version='1.0' encoding='windows-1252'?><rss><channel/>\x93</rss>";
+    assert_eq!(Feed::from_bytes(rss).err(), unsupported("windows-1252"));
+}
+#[test]
+fn ascii() {
+    // ASCII is the same in ISO-8859-1.
+    let xml = "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n\
+        <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\
+        <url><loc>https://example.com/</loc></url></urlset>";
+    let doc = Document::from_bytes(xml.as_bytes()).unwrap();
+    assert_eq!(doc.iterate().unwrap().count(), 1);
+    let utf8 = MISDECLARED.replace("ISO-8859-1", "utf-8");
+    assert!(Document::from_bytes(utf8.as_bytes()).is_ok());
+}
+#[test]
+fn utf_16() {
+    let text: Vec<u8> = "\u{feff}https://example.com/\n"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    assert_eq!(
+        TextSitemap::from_bytes(&text).err(),
+        unsupported("UTF-16LE")
+    );
+    assert_eq!(Sitemap::parse(&text).err(), unsupported("UTF-16LE"));
+    let xml: Vec<u8> = "<?xml version=\"1.0\"?><urlset/>"
+        .encode_utf16()
+        .flat_map(u16::to_be_bytes)
+        .collect();
+    assert_eq!(Document::from_bytes(&xml).err(), unsupported("UTF-16BE"));
+}
+#[cfg(not(feature = "encoding_rs"))]
+#[test]
+fn owned() {
+    assert_eq!(
+        OwnedDocument::from_bytes(LATIN_1).err(),
+        unsupported("ISO-8859-1")
+    );
+}
+#[cfg(feature = "encoding_rs")]
+#[test]
+fn owned() {
+    use sitemap_iter::encoding::{Encoding, Mismatch};
+
+    let doc = OwnedDocument::from_bytes(LATIN_1).unwrap();
+    assert_eq!(doc.entries()[0].location, "https://example.com/café");
+    assert_eq!(doc.encoding_mismatches(), []);
+
+    let doc = OwnedDocument::from_bytes(MISDECLARED.as_bytes()).unwrap();
+    // The WHATWG Encoding Standard decodes ISO-8859-1 as windows-1252.
+    let windows_1252 = Encoding::for_label(b"ISO-8859-1").unwrap();
+    assert_eq!(
+        doc.encoding_mismatches(),
+        [Mismatch::LooksLikeUtf8(windows_1252)]
+    );
+}
@@ -0,0 +1,73 @@
+use sitemap_iter::feed::{Feed, FeedKind};
+use sitemap_iter::{DiagnosticKind, TextPos};
+
+const RSS: &str = "<rss version=\"2.0\">\n<channel>\n  <title>Ä</title>\n\
+    <item><link>https://example.com/a</link><pubDate>Sat, 07 Sep 2002 09:42:31 GMT</pubDate></item>\n\
+    <item><link>https://example.com/b</link><pubDate>yesterday</pubDate></item>\n\
+    <item><title>no link</title></item>\n\
+    </channel>\n</rss>";
+
+#[test]
+fn rss() {
+    let feed = Feed::parse(RSS).unwrap();
+    assert_eq!(feed.kind(), FeedKind::Rss);
+    let entries: Vec<_> = feed.iterate().unwrap().collect();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].location, "https://example.com/a");
+    assert_eq!(entries[0].last_modified, Some("2002-09-07T09:42:31Z"));
+    assert!(entries[0].images.is_empty() && entries[0].extensions.is_empty());
+    assert_eq!(entries[1].location, "https://example.com/b");
+}
+#[test]
+fn atom() {
+    let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+        <entry>
+            <link rel="edit" href="https://example.com/edit"/>
+            <link href="https://example.com/a"/>
+            <updated>2003-12-13T18:30:02Z</updated>
+        </entry>
+    </feed>"#;
+    let feed = Feed::parse(xml).unwrap();
+    assert_eq!(feed.kind(), FeedKind::Atom);
+    let entries: Vec<_> = feed.iterate().unwrap().collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].location, "https://example.com/a");
+    assert_eq!(entries[0].last_modified, Some("2003-12-13T18:30:02Z"));
+}
+#[test]
+fn diagnostics() {
+    let diagnostics: Vec<_> = Feed::parse(RSS)
+        .unwrap()
+        .diagnostics()
+        .into_iter()
+        .map(|diagnostic| (diagnostic.entry, diagnostic.position, diagnostic.kind))
+        .collect();
+    assert_eq!(
+        diagnostics,
+        [
+            (
+                1,
+                TextPos::new(5, 41),
+                DiagnosticKind::InvalidLastModified("yesterday".to_owned())
+            ),
+            (2, TextPos::new(6, 1), DiagnosticKind::MissingLocation),
+        ]
+    );
+}
+#[test]
+fn overflowing_year() {
+    let xml = "<rss version=\"2.0\"><channel><item><link>https://example.com/a</link>\
+        <pubDate>Sat, 07 Sep 99999999999 09:42:31 GMT</pubDate></item></channel></rss>";
+    let feed = Feed::parse(xml).unwrap();
+    let kinds: Vec<_> = feed
+        .diagnostics()
+        .into_iter()
+        .map(|diagnostic| diagnostic.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        [DiagnosticKind::InvalidLastModified(
+            "Sat, 07 Sep 99999999999 09:42:31 GMT".to_owned()
+        )]
+    );
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "gzip")]
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use sitemap_iter::gzip::{self, MaybeGzip};
+use std::io::{Read, Write};
+
+fn compress(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn multiple_members() {
+    let mut bytes = compress(b"<urlset>");
+    bytes.extend(compress(b"</urlset>"));
+    let decompressed = gzip::decompress(&bytes, gzip::DEFAULT_MAX_SIZE).unwrap();
+    assert_eq!(&*decompressed, b"<urlset></urlset>");
+
+    let mut reader = MaybeGzip::new(&bytes[..], gzip::DEFAULT_MAX_SIZE).unwrap();
+    assert!(reader.is_gzip());
+    let mut decompressed = Vec::new();
+    reader.read_to_end(&mut decompressed).unwrap();
+    assert_eq!(decompressed, b"<urlset></urlset>");
+}
+#[test]
+fn too_large() {
+    let mut bytes = compress(b"<urlset>");
+    bytes.extend(compress(b"</urlset>"));
+    let err = gzip::decompress(&bytes, 10).unwrap_err();
+    assert_eq!(
+        err.get_ref().and_then(|err| err.downcast_ref()),
+        Some(&gzip::TooLarge { max_size: 10 })
+    );
+}
+#[test]
+fn plain() {
+    let bytes = b"<urlset></urlset>";
+    assert_eq!(&*gzip::decompress(bytes, 1).unwrap(), bytes);
+}
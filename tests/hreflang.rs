@@ -0,0 +1,167 @@
+use sitemap_iter::hreflang::{is_valid_hreflang, Cluster, Issue, Validator};
+use sitemap_iter::Document;
+
+/// A sitemap with an entry for each location, with `(hreflang, href)` alternates.
+fn sitemap(entries: &[(&str, &[(&str, &str)])]) -> String {
+    let mut xml = String::from(
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:xhtml="http://www.w3.org/1999/xhtml">"#,
+    );
+    for (location, alternates) in entries {
+        xml += &format!("<url><loc>https://example.com/{location}</loc>");
+        for (hreflang, href) in *alternates {
+            xml += &format!(
+                r#"<xhtml:link rel="alternate" hreflang="{hreflang}" href="https://example.com/{href}"/>"#
+            );
+        }
+        xml += "</url>";
+    }
+    xml + "</urlset>"
+}
+fn validate(sitemaps: &[String]) -> Vec<Cluster> {
+    let mut validator = Validator::new();
+    for xml in sitemaps {
+        for entry in Document::parse(xml).unwrap().iterate().unwrap() {
+            validator.add(&entry);
+        }
+    }
+    validator.validate()
+}
+fn url(path: &str) -> String {
+    format!("https://example.com/{path}")
+}
+fn pages(paths: &[&str]) -> Vec<String> {
+    paths.iter().map(|path| url(path)).collect()
+}
+
+#[test]
+fn consistent() {
+    let clusters = validate(&[sitemap(&[
+        ("en", &[("en", "en"), ("de", "de"), ("x-default", "en")]),
+        ("de", &[("en", "en"), ("de", "de"), ("x-default", "en")]),
+        ("alone", &[]),
+    ])]);
+    assert_eq!(
+        clusters,
+        [Cluster {
+            pages: pages(&["de", "en"]),
+            issues: Vec::new(),
+        }]
+    );
+    assert!(clusters[0].is_consistent());
+}
+#[test]
+fn clusters_across_sitemaps() {
+    // a lists b, and c lists b: all three are in the same cluster.
+    let clusters = validate(&[
+        sitemap(&[("a", &[("x-default", "a"), ("en", "a"), ("de", "b")])]),
+        sitemap(&[
+            ("b", &[("en", "a"), ("de", "b"), ("fr", "c")]),
+            ("c", &[("en", "a"), ("de", "b"), ("fr", "c")]),
+            ("d", &[("x-default", "d")]),
+        ]),
+    ]);
+    assert_eq!(
+        clusters,
+        [
+            Cluster {
+                pages: pages(&["a", "b", "c"]),
+                issues: vec![Issue::MissingReturnLink {
+                    from: url("c"),
+                    to: url("a"),
+                }],
+            },
+            Cluster {
+                pages: pages(&["d"]),
+                issues: Vec::new(),
+            },
+        ]
+    );
+}
+#[test]
+fn return_links_and_self_references() {
+    let clusters = validate(&[sitemap(&[
+        (
+            "a/en",
+            &[("en", "a/en"), ("fr", "a/fr"), ("x-default", "a/en")],
+        ),
+        ("a/fr", &[("fr", "a/fr")]),
+        ("b/en", &[("de", "b/de")]),
+    ])]);
+    assert_eq!(
+        clusters,
+        [
+            Cluster {
+                pages: pages(&["a/en", "a/fr"]),
+                issues: vec![Issue::MissingReturnLink {
+                    from: url("a/en"),
+                    to: url("a/fr"),
+                }],
+            },
+            Cluster {
+                pages: pages(&["b/de", "b/en"]),
+                issues: vec![
+                    Issue::MissingSelfReference { page: url("b/en") },
+                    Issue::UnknownAlternate {
+                        page: url("b/en"),
+                        href: url("b/de"),
+                    },
+                    Issue::MissingDefault,
+                ],
+            },
+        ]
+    );
+}
+#[test]
+fn hreflangs() {
+    let clusters = validate(&[sitemap(&[
+        ("uk", &[("en-UK", "uk"), ("en", "us"), ("x-default", "uk")]),
+        ("us", &[("en", "us"), ("EN", "uk"), ("x-default", "uk")]),
+    ])]);
+    assert_eq!(
+        clusters,
+        [Cluster {
+            pages: pages(&["uk", "us"]),
+            issues: vec![
+                Issue::InvalidHreflang {
+                    page: url("uk"),
+                    hreflang: "en-UK".to_owned(),
+                },
+                Issue::DuplicateHreflang {
+                    hreflang: "en".to_owned(),
+                    hrefs: pages(&["uk", "us"]),
+                },
+            ],
+        }]
+    );
+}
+#[test]
+fn valid_hreflang() {
+    for hreflang in [
+        "en",
+        "EN-gb",
+        "de-CH",
+        "zh-Hant",
+        "zh-Hant-TW",
+        "es-419",
+        "yue",
+        "x-default",
+        "X-Default",
+    ] {
+        assert!(is_valid_hreflang(hreflang), "{hreflang}");
+    }
+    for hreflang in [
+        "",
+        "e",
+        "english",
+        "zz",
+        "en-",
+        "en-uk",
+        "en-zz",
+        "en-1234",
+        "zh-Han1",
+        "en-US-extra",
+        "en_US",
+    ] {
+        assert!(!is_valid_hreflang(hreflang), "{hreflang}");
+    }
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "url")]
+
+use sitemap_iter::location::{self, is_percent_encoded, LocationError};
+use sitemap_iter::{DiagnosticKind, Document};
+
+#[test]
+fn parse() {
+    let url = location::parse("  https://example.com/a b\n").unwrap();
+    assert_eq!(url.as_str(), "https://example.com/a%20b");
+    assert_eq!(
+        location::parse("/relative/path"),
+        Err(LocationError::Relative)
+    );
+    assert_eq!(
+        location::parse("ftp://example.com/file"),
+        Err(LocationError::UnsupportedScheme("ftp".to_owned()))
+    );
+    assert_eq!(
+        location::parse("https://example.com:99999/"),
+        Err(LocationError::Invalid(url::ParseError::InvalidPort))
+    );
+}
+#[test]
+fn percent_encoding() {
+    for location in [
+        "https://example.com/",
+        "https://example.com/caf%C3%A9?q=a+b&x=%2f#top",
+        "http://[::1]:8080/~user/(1)*,;=!$'",
+    ] {
+        assert!(is_percent_encoded(location), "{location}");
+    }
+    for location in [
+        "https://example.com/a b",
+        "https://example.com/café",
+        "https://example.com/100%",
+        "https://example.com/%zz",
+        "https://example.com/<tag>",
+        "https://example.com/{}",
+    ] {
+        assert!(!is_percent_encoded(location), "{location}");
+    }
+}
+#[test]
+fn diagnostics() {
+    let long = format!("https://example.com/{}", "a".repeat(2048));
+    let xml = format!(
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+        <url><loc>https://example.com/ok</loc></url>
+        <url><loc>example.com/relative</loc></url>
+        <url><loc>https://example.com/a b</loc></url>
+        <url><loc>{long}</loc></url>
+        <url><loc>https://example.com/{}</loc></url>
+    </urlset>"#,
+        "a".repeat(2048 - "https://example.com/".len())
+    );
+    let doc = Document::parse(&xml).unwrap();
+    let diagnostics: Vec<_> = doc
+        .diagnostics()
+        .unwrap()
+        .into_iter()
+        .map(|diagnostic| (diagnostic.entry, diagnostic.kind))
+        .collect();
+    assert_eq!(
+        diagnostics,
+        [
+            (
+                1,
+                DiagnosticKind::InvalidLocation("example.com/relative".to_owned())
+            ),
+            (
+                2,
+                DiagnosticKind::LocationNotPercentEncoded("https://example.com/a b".to_owned())
+            ),
+            (3, DiagnosticKind::LocationTooLong(long.len())),
+        ]
+    );
+    // Invalid locations are kept by default.
+    assert_eq!(doc.iterate().unwrap().count(), 5);
+}
@@ -0,0 +1,75 @@
+use sitemap_iter::news::{self, is_valid_language};
+use sitemap_iter::{DiagnosticKind, Document};
+
+fn news_entry(location: &str, language: &str) -> String {
+    format!(
+        "<url><loc>{location}</loc><news:news>\
+            <news:publication><news:name>Example</news:name><news:language>{language}</news:language></news:publication>\
+            <news:publication_date>2008-12-23</news:publication_date>\
+            <news:title>Title</news:title>\
+        </news:news></url>"
+    )
+}
+fn urlset(entries: &str) -> String {
+    format!(
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:news="http://www.google.com/schemas/sitemap-news/0.9">{entries}</urlset>"#
+    )
+}
+
+#[test]
+fn languages() {
+    for language in [
+        "en", "EN", "fr", "zh-cn", "zh-tw", "zh-CN", "ZH-TW", "fil", "Haw",
+    ] {
+        assert!(is_valid_language(language), "{language}");
+    }
+    for language in ["", "e", "xx", "zh-hk", "en-us", "english", "f1l", "zh_cn"] {
+        assert!(!is_valid_language(language), "{language}");
+    }
+}
+#[test]
+fn invalid_language() {
+    let xml = urlset(&news_entry("https://example.com/", "english"));
+    let doc = Document::parse(&xml).unwrap();
+    let report = doc.iterate_with_diagnostics().unwrap().next().unwrap();
+    let kinds: Vec<_> = report.diagnostics.into_iter().map(|d| d.kind).collect();
+    assert_eq!(
+        kinds,
+        [DiagnosticKind::InvalidExtensionField {
+            field: "news:language".to_owned(),
+            value: "english".to_owned(),
+        }]
+    );
+    // The language is kept.
+    let news = report.entry.unwrap().news.unwrap();
+    assert_eq!(news.publication.language, "english");
+}
+#[test]
+fn too_many_news_entries() {
+    let mut entries = String::new();
+    for i in 0..=news::MAX_ENTRIES {
+        entries += &news_entry(&format!("https://example.com/{i}"), "en");
+        // Entries without news don't count.
+        entries += "<url><loc>https://example.com/</loc></url>";
+    }
+    let xml = urlset(&entries);
+    let doc = Document::parse(&xml).unwrap();
+    let diagnostics = doc.diagnostics().unwrap();
+    let too_many: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.entry, &diagnostic.kind))
+        .collect();
+    assert_eq!(
+        too_many,
+        [(2 * news::MAX_ENTRIES, &DiagnosticKind::TooManyNewsEntries)]
+    );
+    // The news past the limit are still parsed.
+    assert!(doc.iterate().unwrap().last().unwrap().news.is_none());
+    assert!(doc
+        .iterate()
+        .unwrap()
+        .nth(2 * news::MAX_ENTRIES)
+        .unwrap()
+        .news
+        .is_some());
+}
@@ -0,0 +1,72 @@
+use sitemap_iter::{
+    Document, DocumentKind, Frequency, OwnedDocument, SitemapIndexWriter, SitemapWriter,
+};
+
+const URLSET: &str = r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">
+    <url>
+        <loc>https://example.com/?a=1&amp;b=2</loc>
+        <lastmod>2005-01-01</lastmod>
+        <changefreq>monthly</changefreq>
+        <priority>0.8</priority>
+        <image:image><image:loc>https://example.com/image.jpg</image:loc></image:image>
+    </url>
+    <url><loc>https://example.com/b</loc><priority>2</priority></url>
+    <url><lastmod>2005-01-01</lastmod></url>
+</urlset>"#;
+
+#[test]
+fn round_trip() {
+    let owned = OwnedDocument::parse(URLSET).unwrap();
+    assert_eq!(owned.kind(), DocumentKind::Urlset);
+    assert!(owned.sitemaps().is_empty());
+    assert_eq!(owned.diagnostics().len(), 2);
+    let entries = owned.entries();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].location, "https://example.com/?a=1&b=2");
+    assert_eq!(entries[0].change_frequency, Some(Frequency::Monthly));
+    assert_eq!(
+        entries[0].images[0].location,
+        "https://example.com/image.jpg"
+    );
+    // The same entries as parsing without owning them.
+    let doc = Document::parse(URLSET).unwrap();
+    for (owned, entry) in entries.iter().zip(doc.iterate().unwrap()) {
+        assert_eq!(owned.as_entry(), entry);
+    }
+
+    let mut writer = SitemapWriter::new(Vec::new());
+    for entry in entries {
+        writer.write_entry(&entry.as_entry()).unwrap();
+    }
+    let xml = String::from_utf8(writer.finish().unwrap()).unwrap();
+    let written = OwnedDocument::parse(&xml).unwrap();
+    assert!(written.diagnostics().is_empty());
+    // Only the fields of the protocol are written.
+    let mut expected = owned.into_entries();
+    expected[0].images.clear();
+    assert_eq!(written.into_entries(), expected);
+}
+#[test]
+fn sitemap_index() {
+    let xml = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+        <sitemap><loc>https://example.com/a.xml</loc><lastmod>2004-10-01</lastmod></sitemap>
+        <sitemap><loc>https://example.com/b.xml</loc></sitemap>
+        <sitemap></sitemap>
+    </sitemapindex>"#;
+    let owned = OwnedDocument::from_bytes(xml.as_bytes()).unwrap();
+    assert_eq!(owned.kind(), DocumentKind::SitemapIndex);
+    assert!(owned.entries().is_empty());
+    assert_eq!(owned.diagnostics().len(), 1);
+    let sitemaps = owned.sitemaps();
+    assert_eq!(sitemaps.len(), 2);
+    assert_eq!(sitemaps[0].last_modified.as_deref(), Some("2004-10-01"));
+
+    let mut writer = SitemapIndexWriter::new(Vec::new());
+    for sitemap in sitemaps {
+        writer.write_entry(&sitemap.as_entry()).unwrap();
+    }
+    let written = writer.finish().unwrap();
+    let written = OwnedDocument::from_bytes(&written).unwrap();
+    assert_eq!(written.into_sitemaps(), owned.into_sitemaps());
+}
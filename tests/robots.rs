@@ -0,0 +1,51 @@
+use sitemap_iter::robots::Robots;
+
+fn locations(text: &str) -> Vec<&str> {
+    Robots::parse(text)
+        .sitemaps()
+        .map(|sitemap| sitemap.location)
+        .collect()
+}
+
+#[test]
+fn sitemaps() {
+    let text = "\u{feff}User-agent: *\r\nDisallow: /private\r\n\r\n\
+        Sitemap: https://example.com/a.xml\r\n\
+        sitemap:https://example.com/b.xml\n\
+        SITEMAP : https://example.com/c.xml  \n\
+        Sitemap:\n\
+        Allow: https://example.com/d.xml\n";
+    assert_eq!(
+        locations(text),
+        [
+            "https://example.com/a.xml",
+            "https://example.com/b.xml",
+            "https://example.com/c.xml"
+        ]
+    );
+}
+#[test]
+fn comments() {
+    let text = "# Sitemap: https://example.com/commented.xml\n\
+        Sitemap: https://example.com/a.xml # the main sitemap\n\
+        Sitemap: https://example.com/b.xml\t#tab\n\
+        Sitemap: https://example.com/c.xml#fragment\n\
+        Sitemap: https://example.com/d.xml?q=#1 #comment\n\
+        #Sitemap: https://example.com/commented.xml\n";
+    assert_eq!(
+        locations(text),
+        [
+            "https://example.com/a.xml",
+            "https://example.com/b.xml",
+            "https://example.com/c.xml#fragment",
+            "https://example.com/d.xml?q=#1"
+        ]
+    );
+}
+#[test]
+fn authorizes() {
+    let robots = Robots::parse("Sitemap: https://Example.com/sitemap.xml#main\n");
+    assert!(robots.authorizes("HTTPS://example.COM/sitemap.xml#main"));
+    assert!(!robots.authorizes("https://example.com/sitemap.xml"));
+    assert!(!robots.authorizes("https://example.com/Sitemap.xml#main"));
+}
@@ -0,0 +1,228 @@
+use sitemap_iter::schema::{Violation, ViolationKind};
+use sitemap_iter::{Document, TextPos};
+
+fn violations(xml: &str) -> Vec<Violation> {
+    Document::parse(xml).unwrap().validate_schema()
+}
+fn kinds(xml: &str) -> Vec<ViolationKind> {
+    violations(xml).into_iter().map(|v| v.kind).collect()
+}
+fn urlset(urls: &str) -> String {
+    format!(
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+                   xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+                   xmlns:image="http://www.google.com/schemas/sitemap-image/1.1"
+                   xsi:schemaLocation="http://www.sitemaps.org/schemas/sitemap/0.9 http://www.sitemaps.org/schemas/sitemap/0.9/sitemap.xsd">{urls}</urlset>"#
+    )
+}
+fn url_field(field: &str) -> Vec<ViolationKind> {
+    kinds(&urlset(&format!(
+        "<url><loc>https://example.com/</loc>{field}</url>"
+    )))
+}
+fn unexpected(parent: &str, name: &str, namespace: Option<&str>) -> ViolationKind {
+    ViolationKind::UnexpectedElement {
+        parent: parent.to_owned(),
+        name: name.to_owned(),
+        namespace: namespace.map(str::to_owned),
+    }
+}
+const NS: Option<&str> = Some("http://www.sitemaps.org/schemas/sitemap/0.9");
+
+#[test]
+fn valid() {
+    let xml = urlset(
+        "<url>
+            <loc>https://example.com/</loc>
+            <lastmod>2005-01-01</lastmod>
+            <changefreq>monthly</changefreq>
+            <priority>0.8</priority>
+            <image:image><image:loc>https://example.com/a.png</image:loc></image:image>
+        </url>
+        <url><loc> https://example.com/b </loc><lastmod>2004-12-23T18:00:15+00:00</lastmod></url>
+        <url><loc>https://example.com/c</loc><priority>1</priority></url>
+        <url><loc>https://example.com/d</loc><priority>.5</priority></url>",
+    );
+    assert_eq!(violations(&xml), []);
+    let index = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+        <sitemap><lastmod>2004-10-01T18:23:17+00:00</lastmod><loc>https://example.com/s1.xml</loc></sitemap>
+        <sitemap><loc>https://example.com/s2.xml</loc></sitemap>
+    </sitemapindex>"#;
+    assert_eq!(violations(index), []);
+}
+#[test]
+fn root() {
+    assert_eq!(
+        kinds("<urlset><url/></urlset>"),
+        [ViolationKind::UnexpectedRoot {
+            name: "urlset".to_owned(),
+            namespace: None
+        }]
+    );
+    assert_eq!(
+        kinds(&urlset("")),
+        [ViolationKind::MissingElement {
+            parent: "urlset".to_owned(),
+            name: "url".to_owned()
+        }]
+    );
+    assert_eq!(
+        kinds(&urlset(
+            "<url><loc>https://example.com/</loc></url><sitemap/>"
+        )),
+        [unexpected("urlset", "sitemap", NS)]
+    );
+}
+#[test]
+fn order() {
+    assert_eq!(
+        url_field("<priority>0.5</priority><lastmod>2005-01-01</lastmod>"),
+        [unexpected("url", "lastmod", NS)]
+    );
+    assert_eq!(
+        url_field("<lastmod>2005-01-01</lastmod><lastmod>2005-01-01</lastmod>"),
+        [unexpected("url", "lastmod", NS)]
+    );
+    assert_eq!(
+        url_field("<image:image/><priority>0.5</priority>"),
+        [unexpected("url", "priority", NS)]
+    );
+    assert_eq!(url_field("<foo/>"), [unexpected("url", "foo", NS)]);
+    assert_eq!(
+        url_field(r#"<foo xmlns=""/>"#),
+        [unexpected("url", "foo", None)]
+    );
+    assert_eq!(
+        kinds(&urlset(
+            "<url><lastmod>2005-01-01</lastmod><loc>https://example.com/</loc></url>"
+        )),
+        [
+            unexpected("url", "loc", NS),
+            ViolationKind::MissingElement {
+                parent: "url".to_owned(),
+                name: "loc".to_owned()
+            }
+        ]
+    );
+    assert_eq!(
+        kinds(&urlset("<url><lastmod>2005-01-01</lastmod></url>")),
+        [ViolationKind::MissingElement {
+            parent: "url".to_owned(),
+            name: "loc".to_owned()
+        }]
+    );
+    // The fields of a `<sitemap>` can be in any order, but only once.
+    let index = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+        <sitemap><loc>https://example.com/s1.xml</loc><loc>https://example.com/s2.xml</loc></sitemap>
+    </sitemapindex>"#;
+    assert_eq!(kinds(index), [unexpected("sitemap", "loc", NS)]);
+}
+#[test]
+fn attributes_and_text() {
+    assert_eq!(
+        kinds(&urlset(
+            r#"<url id="1">text<loc>https://example.com/</loc></url>"#
+        )),
+        [
+            ViolationKind::UnexpectedAttribute {
+                element: "url".to_owned(),
+                name: "id".to_owned(),
+                namespace: None
+            },
+            ViolationKind::UnexpectedText {
+                element: "url".to_owned()
+            }
+        ]
+    );
+}
+#[test]
+fn location() {
+    assert_eq!(
+        kinds(&urlset("<url><loc>http://a.b</loc></url>")),
+        [ViolationKind::LocationLength(10)]
+    );
+    let long = format!("https://example.com/{}", "a".repeat(2100));
+    assert_eq!(
+        kinds(&urlset(&format!("<url><loc>{long}</loc></url>"))),
+        [ViolationKind::LocationLength(long.len())]
+    );
+}
+#[test]
+fn last_modified() {
+    for valid in [
+        "2005-01-01",
+        "2005-01-01T12:00:00",
+        "2005-01-01T12:00:00Z",
+        "2005-01-01T12:00:00.5-14:00",
+        "2005-01-01Z",
+        " 2005-01-01 ",
+    ] {
+        assert_eq!(
+            url_field(&format!("<lastmod>{valid}</lastmod>")),
+            [],
+            "{valid:?}"
+        );
+    }
+    // Unlike W3C Datetime, `xsd:date` and `xsd:dateTime` require a full date and seconds.
+    for invalid in [
+        "2005",
+        "2005-01",
+        "2005-1-1",
+        "2005-01-01T12:00",
+        "2005-01-01T12:00:00+14:30",
+        "2005-01-01T12:00:00+0100",
+        "2005-13-01",
+        "yesterday",
+    ] {
+        assert_eq!(
+            url_field(&format!("<lastmod>{invalid}</lastmod>")),
+            [ViolationKind::InvalidLastModified(invalid.to_owned())],
+            "{invalid:?}"
+        );
+    }
+}
+#[test]
+fn change_frequency() {
+    assert_eq!(url_field("<changefreq>always</changefreq>"), []);
+    for invalid in ["Daily", " daily", "sometimes", ""] {
+        assert_eq!(
+            url_field(&format!("<changefreq>{invalid}</changefreq>")),
+            [ViolationKind::InvalidChangeFrequency(invalid.to_owned())],
+            "{invalid:?}"
+        );
+    }
+}
+#[test]
+fn priority() {
+    for valid in ["0", "0.0", "1.0", "+0.5", " 0.3 ", "1.", "-0"] {
+        assert_eq!(
+            url_field(&format!("<priority>{valid}</priority>")),
+            [],
+            "{valid:?}"
+        );
+    }
+    for invalid in ["1e-1", "NaN", "high", "", ".", "0,5"] {
+        assert_eq!(
+            url_field(&format!("<priority>{invalid}</priority>")),
+            [ViolationKind::InvalidPriority(invalid.to_owned())],
+            "{invalid:?}"
+        );
+    }
+    assert_eq!(
+        url_field("<priority>1.5</priority>"),
+        [ViolationKind::PriorityOutOfRange("1.5".to_owned())]
+    );
+    assert_eq!(
+        url_field("<priority>-0.1</priority>"),
+        [ViolationKind::PriorityOutOfRange("-0.1".to_owned())]
+    );
+}
+#[test]
+fn position() {
+    let xml = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n\
+        <url>\n  <loc>https://example.com/</loc>\n  <changefreq>weekly </changefreq>\n</url>\n\
+        </urlset>";
+    let found = violations(xml);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].position, TextPos::new(4, 3));
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "url")]
+
+use sitemap_iter::location::Url;
+use sitemap_iter::robots::Robots;
+use sitemap_iter::scope::{classify, classify_location, classify_with_robots, Scope};
+
+fn url(text: &str) -> Url {
+    Url::parse(text).unwrap()
+}
+
+#[test]
+fn classify_locations() {
+    let sitemap = url("http://example.com/catalog/sitemap.xml");
+    let cases = [
+        ("http://example.com/catalog/shoes", Scope::InScope),
+        ("http://example.com/catalog/", Scope::InScope),
+        ("http://example.com/catalog/a/b?c=d", Scope::InScope),
+        ("http://EXAMPLE.com:80/catalog/shoes", Scope::InScope),
+        ("http://example.com/catalog", Scope::OutOfPath),
+        ("http://example.com/catalogue/shoes", Scope::OutOfPath),
+        ("http://example.com/images/", Scope::OutOfPath),
+        ("https://example.com/catalog/shoes", Scope::SchemeMismatch),
+        ("http://example.com:8080/catalog/shoes", Scope::CrossHost),
+        ("http://www.example.com/catalog/shoes", Scope::CrossHost),
+        ("https://example.org/catalog/shoes", Scope::CrossHost),
+    ];
+    for (location, scope) in cases {
+        assert_eq!(classify(&sitemap, &url(location)), scope, "{location}");
+    }
+}
+#[test]
+fn root_sitemap() {
+    let sitemap = url("https://example.com/sitemap.xml");
+    assert!(classify(&sitemap, &url("https://example.com/")).is_in_scope());
+    assert!(classify(&sitemap, &url("https://example.com/any/page")).is_in_scope());
+    assert_eq!(
+        classify_location(&sitemap, " https://example.com/page\n"),
+        Ok(Scope::InScope)
+    );
+    assert!(classify_location(&sitemap, "/relative").is_err());
+}
+#[test]
+fn cross_submission() {
+    let sitemap = url("https://sitemaps.example.org/example.com.xml");
+    let location = url("https://example.com/page");
+    let robots = Robots::parse("Sitemap: https://sitemaps.example.org/example.com.xml\n");
+    assert_eq!(
+        classify_with_robots(&sitemap, &location, &robots),
+        Scope::InScope
+    );
+    let robots = Robots::parse("Sitemap: https://example.com/sitemap.xml\n");
+    assert_eq!(
+        classify_with_robots(&sitemap, &location, &robots),
+        Scope::CrossHost
+    );
+}
@@ -0,0 +1,187 @@
+use sitemap_iter::stream::{self, StreamParser};
+use sitemap_iter::{DiagnosticKind, Document, Error, ParseOptions};
+
+/// Check that the [`StreamParser`] gives the same reports for the `<url>`s as [`Document`].
+fn assert_same_urls(xml: &str, options: ParseOptions) {
+    let doc = Document::parse(xml).unwrap().options(options.clone());
+    let expected: Vec<_> = doc.iterate_with_diagnostics().unwrap().collect();
+    let mut parser = StreamParser::with_options(xml.as_bytes(), options).unwrap();
+    let mut index = 0;
+    while let Some(report) = parser.next_entry_with_diagnostics().unwrap() {
+        assert_eq!(report, expected[index], "entry {index}");
+        index += 1;
+    }
+    assert_eq!(index, expected.len());
+}
+/// Check that the [`StreamParser`] gives the same reports for the `<sitemap>`s as [`Document`].
+fn assert_same_sitemaps(xml: &str, options: ParseOptions) {
+    let doc = Document::parse(xml).unwrap().options(options.clone());
+    let expected: Vec<_> = doc.iterate_sitemaps_with_diagnostics().unwrap().collect();
+    let mut parser = StreamParser::with_options(xml.as_bytes(), options).unwrap();
+    let mut index = 0;
+    while let Some(report) = parser.next_sitemap_with_diagnostics().unwrap() {
+        assert_eq!(report, expected[index], "entry {index}");
+        index += 1;
+    }
+    assert_eq!(index, expected.len());
+}
+
+const URLSET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:image="http://www.google.com/schemas/sitemap-image/1.1"
+        xmlns:xhtml="http://www.w3.org/1999/xhtml"
+        xmlns:custom="https://example.com/custom">
+  <url>
+    <loc>https://example.com/?a=1&amp;b=2</loc>
+    <lastmod>2023-01-02</lastmod>
+    <changefreq>daily</changefreq>
+    <priority>0.8</priority>
+  </url>
+  <url>
+    <loc><![CDATA[https://example.com/cdata?a=1&b=2]]></loc>
+    <image:image>
+      <image:loc>https://example.com/fish.png</image:loc>
+      <image:title>Fish &amp; chips &#x263A;</image:title>
+    </image:image>
+  </url>
+  <url>
+    <loc>https://example.com/<![CDATA[mixed]]>?x=&lt;y&gt;</loc>
+    <xhtml:link rel="alternate" hreflang="en" href="https://example.com/en"/>
+    <xhtml:link rel="alternate" hreflang="de"/>
+    <xhtml:link rel="canonical" href="https://example.com/"/>
+    <image:image/>
+    <custom:flag enabled="yes"/>
+    <custom:empty></custom:empty>
+    <plain xmlns="">text</plain>
+  </url>
+  <url>
+    <lastmod>yesterday</lastmod>
+  </url>
+  <url>
+    <loc>https://example.com/priority</loc>
+    <priority>1.5</priority>
+    <priority>high</priority>
+    <changefreq>sometimes</changefreq>
+  </url>
+</urlset>
+"#;
+
+#[test]
+fn urls() {
+    assert_same_urls(URLSET, ParseOptions::default());
+    assert_same_urls(URLSET, ParseOptions::lenient());
+}
+#[test]
+fn rejected() {
+    let options = ParseOptions::strict();
+    let doc = Document::parse(URLSET).unwrap().options(options.clone());
+    let expected = match doc.iterate_with_diagnostics() {
+        Err(Error::Rejected(diagnostic)) => diagnostic,
+        _ => panic!("the document should be rejected"),
+    };
+    let mut parser = StreamParser::with_options(URLSET.as_bytes(), options).unwrap();
+    let err = loop {
+        match parser.next_entry_with_diagnostics() {
+            Ok(Some(_)) => {}
+            Ok(None) => panic!("the document should be rejected"),
+            Err(err) => break err,
+        }
+    };
+    assert!(
+        matches!(err, stream::Error::Document(Error::Rejected(diagnostic)) if diagnostic == expected)
+    );
+}
+#[test]
+fn entities_and_cdata() {
+    let mut parser = StreamParser::new(URLSET.as_bytes()).unwrap();
+    let first = parser.next_entry().unwrap().unwrap();
+    assert_eq!(first.location, "https://example.com/?a=1&b=2");
+    let second = parser.next_entry().unwrap().unwrap();
+    assert_eq!(second.location, "https://example.com/cdata?a=1&b=2");
+    assert_eq!(second.images[0].title, Some("Fish & chips \u{263a}"));
+    let third = parser.next_entry().unwrap().unwrap();
+    assert_eq!(third.location, "https://example.com/mixed?x=<y>");
+}
+#[test]
+fn self_closing_extensions() {
+    let mut parser = StreamParser::new(URLSET.as_bytes()).unwrap();
+    parser.next_entry().unwrap();
+    parser.next_entry().unwrap();
+    let report = parser.next_entry_with_diagnostics().unwrap().unwrap();
+    let entry = report.entry.unwrap();
+    assert_eq!(entry.alternates.len(), 1);
+    assert!(entry.images.is_empty());
+    // The two `<xhtml:link>` which aren't alternates are kept as extensions.
+    assert_eq!(entry.extensions.len(), 5);
+    assert_eq!(entry.extensions[0].attribute("hreflang"), Some("de"));
+    assert_eq!(entry.extensions[1].attribute("rel"), Some("canonical"));
+    assert_eq!(entry.extensions[4].namespace, None);
+    let missing = report
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| {
+            matches!(
+                diagnostic.kind,
+                DiagnosticKind::MissingExtensionField { .. }
+            )
+        })
+        .count();
+    assert_eq!(missing, 2);
+}
+#[test]
+fn max_entries() {
+    for max in 0..=6 {
+        assert_same_urls(URLSET, ParseOptions::default().max_entries(max));
+    }
+    let mut parser =
+        StreamParser::with_options(URLSET.as_bytes(), ParseOptions::default().max_entries(2))
+            .unwrap();
+    assert!(parser.next_entry_with_diagnostics().unwrap().is_some());
+    assert!(parser.next_entry_with_diagnostics().unwrap().is_some());
+    assert!(parser.next_entry_with_diagnostics().unwrap().is_none());
+}
+#[test]
+fn max_size() {
+    let max_size = URLSET.find("<lastmod>yesterday").unwrap() as u64;
+    let options = ParseOptions::default().max_size(max_size);
+    assert_eq!(
+        Document::parse_with_options(URLSET, options.clone()).err(),
+        Some(Error::TooLarge(max_size))
+    );
+    let mut parser = StreamParser::with_options(URLSET.as_bytes(), options).unwrap();
+    let mut entries = 0;
+    let err = loop {
+        match parser.next_entry_with_diagnostics() {
+            Ok(Some(_)) => entries += 1,
+            Ok(None) => panic!("the document should be truncated"),
+            Err(err) => break err,
+        }
+    };
+    assert_eq!(entries, 3);
+    assert!(matches!(
+        err,
+        stream::Error::Document(Error::TooLarge(max)) if max == max_size
+    ));
+
+    // The whole document fits.
+    let options = ParseOptions::default().max_size(URLSET.len() as u64);
+    assert_same_urls(URLSET, options);
+}
+#[test]
+fn max_size_before_root() {
+    let options = ParseOptions::default().max_size(10);
+    assert!(matches!(
+        StreamParser::with_options(URLSET.as_bytes(), options),
+        Err(stream::Error::Document(Error::TooLarge(10)))
+    ));
+}
+#[test]
+fn sitemaps() {
+    let xml = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+        <sitemap><loc>https://example.com/a.xml</loc><lastmod>2023-01-02T03:04:05Z</lastmod></sitemap>
+        <sitemap><lastmod>2023</lastmod></sitemap>
+        <sitemap><loc><![CDATA[https://example.com/b.xml?x&y]]></loc><lastmod>never</lastmod></sitemap>
+    </sitemapindex>"#;
+    assert_same_sitemaps(xml, ParseOptions::default());
+    assert_same_sitemaps(xml, ParseOptions::default().max_entries(1));
+}
@@ -0,0 +1,110 @@
+use sitemap_iter::{
+    Diagnostic, DiagnosticKind, Error, InvalidPolicy, ParseOptions, Severity, TextPos, TextSitemap,
+};
+
+const TEXT: &str = "\u{feff}https://example.com/a\n  https://example.com/b  \r\n\n\
+    \t\nhttps://example.com/c\n";
+
+#[test]
+fn lines() {
+    let sitemap = TextSitemap::parse(TEXT);
+    let entries: Vec<_> = sitemap
+        .iterate()
+        .unwrap()
+        .map(|entry| entry.location)
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            "https://example.com/a",
+            "https://example.com/b",
+            "https://example.com/c"
+        ]
+    );
+    let reports: Vec<_> = sitemap
+        .iterate_with_diagnostics()
+        .unwrap()
+        .map(|report| (report.index, report.entry.is_some()))
+        .collect();
+    assert_eq!(
+        reports,
+        [(0, true), (1, true), (2, false), (3, false), (4, true)]
+    );
+}
+#[test]
+fn blank_lines() {
+    let blank_line = |line: usize, severity| Diagnostic {
+        entry: line - 1,
+        position: TextPos::new(line as u32, 1),
+        severity,
+        kind: DiagnosticKind::BlankLine,
+    };
+    assert_eq!(
+        TextSitemap::parse(TEXT).diagnostics(),
+        [
+            blank_line(3, Severity::Warning),
+            blank_line(4, Severity::Warning)
+        ]
+    );
+    let options = ParseOptions::default().blank_line(InvalidPolicy::SkipEntry);
+    assert_eq!(
+        TextSitemap::parse(TEXT).options(options).diagnostics(),
+        [
+            blank_line(3, Severity::Error),
+            blank_line(4, Severity::Error)
+        ]
+    );
+    let sitemap = TextSitemap::parse(TEXT).options(ParseOptions::strict());
+    assert_eq!(
+        sitemap.iterate_with_diagnostics().err(),
+        Some(Error::Rejected(blank_line(3, Severity::Fatal)))
+    );
+}
+#[test]
+fn invalid_location() {
+    let text = "https://example.com/a\nnot a url\n";
+    let diagnostics: Vec<_> = TextSitemap::parse(text)
+        .diagnostics()
+        .into_iter()
+        .map(|diagnostic| (diagnostic.entry, diagnostic.position, diagnostic.kind))
+        .collect();
+    if cfg!(feature = "url") {
+        assert_eq!(
+            diagnostics,
+            [(
+                1,
+                TextPos::new(2, 1),
+                DiagnosticKind::InvalidLocation("not a url".to_owned())
+            )]
+        );
+    } else {
+        assert!(diagnostics.is_empty());
+    }
+}
+#[test]
+fn max_entries() {
+    let sitemap = TextSitemap::parse(TEXT).options(ParseOptions::default().max_entries(2));
+    let reports: Vec<_> = sitemap
+        .iterate_with_diagnostics()
+        .unwrap()
+        .map(|report| report.index)
+        .collect();
+    assert_eq!(reports, [0, 1]);
+    // Blank lines aren't entries.
+    let sitemap = TextSitemap::parse(TEXT).options(ParseOptions::default().max_entries(3));
+    assert_eq!(sitemap.iterate().unwrap().count(), 3);
+}
+#[test]
+fn too_many_entries() {
+    let mut text = "\n".repeat(10);
+    for i in 0..=sitemap_iter::writer::MAX_ENTRIES {
+        text += &format!("https://example.com/{i}\n");
+    }
+    let diagnostics = TextSitemap::parse(&text).diagnostics();
+    let too_many: Vec<_> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.kind == DiagnosticKind::TooManyEntries)
+        .map(|diagnostic| diagnostic.entry)
+        .collect();
+    assert_eq!(too_many, [10 + sitemap_iter::writer::MAX_ENTRIES]);
+}
@@ -0,0 +1,101 @@
+use sitemap_iter::{DiagnosticKind, Document, Error, ParseOptions, RangePolicy, Severity};
+
+#[test]
+fn missing_content_and_player_location() {
+    let xml = r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+            xmlns:video="http://www.google.com/schemas/sitemap-video/1.1">
+        <url>
+            <loc>https://example.com/video</loc>
+            <video:video>
+                <video:thumbnail_loc>https://example.com/thumb.jpg</video:thumbnail_loc>
+                <video:title>Title</video:title>
+                <video:description>Description</video:description>
+            </video:video>
+        </url>
+    </urlset>"#;
+    let doc = Document::parse(xml).unwrap();
+    let report = doc.iterate_with_diagnostics().unwrap().next().unwrap();
+    assert!(report.entry.unwrap().videos.is_empty());
+    let kinds: Vec<_> = report.diagnostics.into_iter().map(|d| d.kind).collect();
+    assert_eq!(
+        kinds,
+        [DiagnosticKind::MissingOneOfExtensionFields {
+            element: "video:video".to_owned(),
+            fields: vec![
+                "video:content_loc".to_owned(),
+                "video:player_loc".to_owned()
+            ],
+        }]
+    );
+    assert_eq!(
+        kinds[0].to_string(),
+        "Expected one of <video:content_loc> or <video:player_loc> in <video:video>, but found none."
+    );
+}
+#[test]
+fn out_of_range() {
+    let xml = r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+            xmlns:video="http://www.google.com/schemas/sitemap-video/1.1">
+        <url>
+            <loc>https://example.com/video</loc>
+            <video:video>
+                <video:thumbnail_loc>https://example.com/thumb.jpg</video:thumbnail_loc>
+                <video:title>Title</video:title>
+                <video:description>Description</video:description>
+                <video:content_loc>https://example.com/video.mp4</video:content_loc>
+                <video:duration>30000</video:duration>
+                <video:rating>-1</video:rating>
+            </video:video>
+        </url>
+    </urlset>"#;
+    // The duration and rating of the video, and the diagnostics.
+    let parse = |policy| {
+        let doc = Document::parse(xml)
+            .unwrap()
+            .options(ParseOptions::default().extension_out_of_range(policy));
+        let report = doc.iterate_with_diagnostics()?.next().unwrap();
+        let video = report
+            .entry
+            .map(|entry| (entry.videos[0].duration, entry.videos[0].rating));
+        let diagnostics: Vec<_> = report
+            .diagnostics
+            .into_iter()
+            .map(|d| (d.kind, d.severity))
+            .collect();
+        Ok::<_, Error>((video, diagnostics))
+    };
+    let out_of_range = |severity| {
+        vec![
+            (
+                DiagnosticKind::ExtensionFieldOutOfRange {
+                    field: "video:duration".to_owned(),
+                    value: "30000".to_owned(),
+                },
+                severity,
+            ),
+            (
+                DiagnosticKind::ExtensionFieldOutOfRange {
+                    field: "video:rating".to_owned(),
+                    value: "-1".to_owned(),
+                },
+                severity,
+            ),
+        ]
+    };
+    assert_eq!(
+        parse(RangePolicy::Ignore),
+        Ok((Some((None, None)), out_of_range(Severity::Warning)))
+    );
+    assert_eq!(
+        parse(RangePolicy::Clamp),
+        Ok((
+            Some((Some(28800), Some(0.0))),
+            out_of_range(Severity::Warning)
+        ))
+    );
+    assert_eq!(
+        parse(RangePolicy::SkipEntry),
+        Ok((None, out_of_range(Severity::Error)))
+    );
+    assert!(matches!(parse(RangePolicy::Fail), Err(Error::Rejected(_))));
+}
@@ -0,0 +1,151 @@
+use sitemap_iter::writer::{
+    self, IndexTooLarge, MemorySink, SitemapIndexWriter, SitemapWriter, SplittingWriter,
+};
+use sitemap_iter::{Document, SitemapEntry, UrlEntry};
+use std::borrow::Cow;
+use std::io;
+
+fn index_error(err: io::Error) -> IndexTooLarge {
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    *err.get_ref().unwrap().downcast_ref().unwrap()
+}
+
+#[test]
+fn split() {
+    let mut writer = SplittingWriter::new(MemorySink::new(), "https://example.com").max_entries(2);
+    for _ in 0..5 {
+        writer
+            .write_entry(&UrlEntry::new("https://example.com/page"))
+            .unwrap();
+    }
+    let sink = writer.finish().unwrap();
+    assert_eq!(sink.files.len(), 4);
+    let index = std::str::from_utf8(&sink.files["sitemap_index.xml"]).unwrap();
+    let doc = Document::parse(index).unwrap();
+    let locations: Vec<_> = doc
+        .iterate_sitemaps()
+        .unwrap()
+        .map(|e| e.location)
+        .collect();
+    assert_eq!(
+        locations,
+        [
+            "https://example.com/sitemap-1.xml",
+            "https://example.com/sitemap-2.xml",
+            "https://example.com/sitemap-3.xml"
+        ]
+    );
+}
+#[test]
+fn too_many_sitemaps() {
+    let mut writer = SplittingWriter::new(MemorySink::new(), "https://example.com").max_entries(1);
+    for _ in 0..writer::MAX_ENTRIES {
+        writer
+            .write_entry(&UrlEntry::new("https://example.com/page"))
+            .unwrap();
+    }
+    let err = writer
+        .write_entry(&UrlEntry::new("https://example.com/page"))
+        .unwrap_err();
+    assert_eq!(
+        index_error(err),
+        IndexTooLarge::Sitemaps(writer::MAX_ENTRIES + 1)
+    );
+    assert_eq!(writer.parts().len(), writer::MAX_ENTRIES);
+    // The sitemaps written so far can still be indexed.
+    writer.finish().unwrap();
+}
+#[test]
+fn index_too_large() {
+    let base_url = format!("https://example.com/{}/", "a".repeat(2100));
+    let mut writer = SplittingWriter::new(MemorySink::new(), base_url).max_entries(1);
+    for _ in 0..26_000 {
+        writer
+            .write_entry(&UrlEntry::new("https://example.com/page"))
+            .unwrap();
+    }
+    let err = writer.finish().unwrap_err();
+    assert!(matches!(
+        index_error(err),
+        IndexTooLarge::Size(size) if size > writer::MAX_SIZE
+    ));
+}
+#[test]
+fn escape() {
+    let mut entry = UrlEntry::new("https://example.com/?a=1&b=<2>");
+    entry.last_modified = Some("'2004' \"10\"");
+    let mut writer = SitemapWriter::new(Vec::new());
+    writer.write_entry(&entry).unwrap();
+    let xml = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert!(xml.contains(
+        "<loc>https://example.com/?a=1&amp;b=&lt;2&gt;</loc>\
+        <lastmod>&apos;2004&apos; &quot;10&quot;</lastmod>"
+    ));
+    // The text round-trips through the parser.
+    let doc = Document::parse(&xml).unwrap();
+    let parsed = doc.iterate().unwrap().next().unwrap();
+    assert_eq!(parsed.location, entry.location);
+    assert_eq!(parsed.last_modified, entry.last_modified);
+
+    let mut index = SitemapIndexWriter::new(Vec::new());
+    index
+        .write_entry(&SitemapEntry {
+            location: "https://example.com/sitemap.xml?a&b",
+            last_modified: None,
+        })
+        .unwrap();
+    let xml = String::from_utf8(index.finish().unwrap()).unwrap();
+    assert!(xml.contains("<loc>https://example.com/sitemap.xml?a&amp;b</loc>"));
+
+    assert_eq!(
+        writer::escape("<a href='x'>&\"</a>"),
+        "&lt;a href=&apos;x&apos;&gt;&amp;&quot;&lt;/a&gt;"
+    );
+    assert!(matches!(writer::escape("plain"), Cow::Borrowed("plain")));
+}
+#[test]
+fn invalid_priority() {
+    for priority in [f32::NAN, -0.1, 1.5, f32::INFINITY] {
+        let mut entry = UrlEntry::new("https://example.com/");
+        entry.priority = Some(priority);
+        let mut writer = SitemapWriter::new(Vec::new());
+        let err = writer.write_entry(&entry).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{priority}");
+        assert_eq!(writer.entries_written(), 0);
+
+        let mut writer = SplittingWriter::new(MemorySink::new(), "https://example.com");
+        let err = writer.write_entry(&entry).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{priority}");
+        assert!(writer.parts().is_empty());
+    }
+    for priority in [0.0, 0.5, 1.0] {
+        let mut entry = UrlEntry::new("https://example.com/");
+        entry.priority = Some(priority);
+        SitemapWriter::new(Vec::new()).write_entry(&entry).unwrap();
+    }
+}
+#[test]
+fn entry_too_large() {
+    let long = format!("https://example.com/{}", "a".repeat(200));
+    let mut writer = SplittingWriter::new(MemorySink::new(), "https://example.com").max_size(200);
+    let err = writer.write_entry(&UrlEntry::new(&long)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(writer.parts().is_empty());
+
+    // Each entry fits in an empty sitemap, but not two of them.
+    let location = format!("https://example.com/{}", "a".repeat(60));
+    let mut writer = writer.max_size(250);
+    for _ in 0..2 {
+        writer.write_entry(&UrlEntry::new(&location)).unwrap();
+    }
+    let sink = writer.finish().unwrap();
+    assert_eq!(sink.files.len(), 3);
+    assert!(sink.files.values().all(
+        |file| file.len() <= 250 && Document::parse(std::str::from_utf8(file).unwrap()).is_ok()
+    ));
+}
+#[test]
+#[should_panic(expected = "max_entries must be at least 1")]
+fn zero_max_entries() {
+    let _ = SplittingWriter::new(MemorySink::new(), "https://example.com").max_entries(0);
+}
@@ -0,0 +1,67 @@
+use sitemap_iter::xhtml::Alternate;
+use sitemap_iter::{DiagnosticKind, Document};
+
+const XML: &str = r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:xhtml="http://www.w3.org/1999/xhtml" xmlns:vendor="https://example.com/vendor">
+    <url>
+        <loc>https://example.com/en</loc>
+        <xhtml:link rel="alternate" hreflang="de" href="https://example.com/de"/>
+        <xhtml:link rel="canonical" href="https://example.com/en"/>
+        <xhtml:link rel="alternate" href="https://example.com/fr"/>
+        <vendor:rating scale="5">4</vendor:rating>
+    </url>
+</urlset>"#;
+
+#[test]
+fn other_links_are_extensions() {
+    let doc = Document::parse(XML).unwrap();
+    let report = doc.iterate_with_diagnostics().unwrap().next().unwrap();
+    let kinds: Vec<_> = report.diagnostics.into_iter().map(|d| d.kind).collect();
+    assert_eq!(
+        kinds,
+        [DiagnosticKind::MissingExtensionField {
+            element: "xhtml:link".to_owned(),
+            field: "hreflang".to_owned(),
+        }]
+    );
+    let entry = report.entry.unwrap();
+    assert_eq!(
+        entry.alternates,
+        [Alternate {
+            hreflang: "de",
+            href: "https://example.com/de",
+        }]
+    );
+    let extensions: Vec<_> = entry
+        .extensions
+        .iter()
+        .map(|extension| {
+            (
+                extension.namespace.unwrap(),
+                extension.name,
+                extension.attribute("rel"),
+                extension.attribute("href"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        extensions,
+        [
+            (
+                "http://www.w3.org/1999/xhtml",
+                "link",
+                Some("canonical"),
+                Some("https://example.com/en")
+            ),
+            (
+                "http://www.w3.org/1999/xhtml",
+                "link",
+                Some("alternate"),
+                Some("https://example.com/fr")
+            ),
+            ("https://example.com/vendor", "rating", None, None),
+        ]
+    );
+    assert_eq!(entry.extensions[2].attribute("scale"), Some("5"));
+    assert_eq!(entry.extensions[2].text, Some("4"));
+}